@@ -0,0 +1,170 @@
+use std::path::PathBuf;
+
+use clap::{CommandFactory, Parser, Subcommand, error::ErrorKind};
+
+use crate::{
+    bar_loading::BarLoading, block::Block, cost_model::CostModelKind, requirement::Requirement,
+    rounding::Rounding, unit::Unit,
+};
+
+#[derive(Parser)]
+#[command(subcommand_precedence_over_arg = true)]
+pub struct Args {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// The session in order, e.g. `22.5d 60b`. Requirements joined by pluses, e.g. `22.5d+60b`,
+    /// are loaded at the same time, and those joined by commas, e.g. `10d,12.5d`, can be done in
+    /// whichever order takes the fewest plate changes. Any weight in a range, e.g. `20-24d`, or
+    /// within a tolerance, e.g. `60b~2.5`, will do. Exercises can be named, with sets and reps,
+    /// e.g. `incline:3x10@22.5d`.
+    #[arg(value_parser = clap::value_parser!(Block))]
+    pub requirements: Vec<Block>,
+
+    /// Session file to read the requirements from instead, or `-` for standard input. Either
+    /// text with a block in the same syntax on each line and `#` comments, or a JSON list of
+    /// blocks.
+    #[arg(long, conflicts_with = "requirements")]
+    pub session: Option<PathBuf>,
+
+    /// Plate inventory file.
+    #[arg(long, global = true, default_value = "data/plates.json")]
+    pub plates: PathBuf,
+
+    /// Collar inventory file.
+    #[arg(long, global = true, default_value = "data/collars.json")]
+    pub collars: PathBuf,
+
+    /// Dumbbell handle inventory file.
+    #[arg(long, global = true, default_value = "data/dumbbells.json")]
+    pub dumbbells: PathBuf,
+
+    /// Barbell inventory file.
+    #[arg(long, global = true, default_value = "data/barbells.json")]
+    pub barbells: PathBuf,
+
+    /// EZ curl bar inventory file.
+    #[arg(long, global = true, default_value = "data/ez_bars.json")]
+    pub ez_bars: PathBuf,
+
+    /// Trap bar inventory file.
+    #[arg(long, global = true, default_value = "data/trap_bars.json")]
+    pub trap_bars: PathBuf,
+
+    /// Fixed-weight implement inventory file, e.g. kettlebells. Each is a single implement, so
+    /// a pair is asked for as a superset, e.g. `16f+16f`.
+    #[arg(long, global = true, default_value = "data/fixed.json")]
+    pub fixed: PathBuf,
+
+    /// File recording what is left on the bars between sessions.
+    #[arg(long, global = true, default_value = "data/state.json")]
+    pub state: PathBuf,
+
+    /// The unit weights are displayed in: kg or lb. Weights can be given in either, e.g. `45lbb`
+    /// or `b:20kg,10lb`, and are in kg unless they say otherwise.
+    #[arg(long, global = true, default_value_t = Unit::Kg)]
+    pub unit: Unit,
+
+    /// Lift profile with the max of each lift, for requirements given as a percentage of it,
+    /// e.g. `bench:80%b`. A list of entries like `{ "lift": "bench", "kind": "b", "max": 100,
+    /// "unit": "kg" }`, with the max in grams if no unit is given.
+    #[arg(long, global = true, default_value = "data/profile.json")]
+    pub profile: PathBuf,
+
+    /// How plate changes are weighed against each other: unit, weight or count.
+    #[arg(long, default_value_t = CostModelKind::Unit)]
+    pub cost: CostModelKind,
+
+    /// What to do when a weight can't be built exactly: exact, nearest, down, up, or a
+    /// tolerance in kg either side, e.g. `2.5`. Requirements can round themselves, e.g.
+    /// `23d/nearest`.
+    #[arg(long, global = true, default_value_t = Rounding::Exact)]
+    pub rounding: Rounding,
+
+    /// What is loaded on a bar before the session, e.g. `b:20,10` for a barbell with 20kg and
+    /// 10kg on each side. Bars that aren't listed are taken to be bare once any is given.
+    /// Defaults to the saved state.
+    #[arg(long, value_parser = clap::value_parser!(BarLoading))]
+    pub start: Vec<BarLoading>,
+
+    /// Strip every bar once the session is over.
+    #[arg(long)]
+    pub strip: bool,
+
+    /// Don't save where the bars are left once the session is over.
+    #[arg(long)]
+    pub no_save: bool,
+}
+
+impl Args {
+    /// Rejects a session given along with a subcommand, which would otherwise be left out
+    /// without a word, since subcommands are picked out from among the requirements so that
+    /// the global options can come before them.
+    ///
+    /// # Errors
+    /// If a subcommand is given along with requirements or a session file.
+    ///
+    pub fn checked(self) -> Result<Self, clap::Error> {
+        if self.command.is_some() && (!self.requirements.is_empty() || self.session.is_some()) {
+            return Err(Args::command().error(
+                ErrorKind::ArgumentConflict,
+                "Requirements can't be given along with a subcommand.",
+            ));
+        }
+
+        Ok(self)
+    }
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Show, reset or edit what is saved as loaded on the bars.
+    #[command(subcommand)]
+    State(StateCommand),
+    /// Ramp up to a working set, adding a single plate for each warm-up set.
+    WarmUp {
+        /// The working set, e.g. `100b`.
+        #[arg(value_parser = clap::value_parser!(Requirement))]
+        requirement: Requirement,
+
+        /// How many warm-up sets to spread evenly below the working weight, up to 20.
+        #[arg(
+            long,
+            default_value_t = 3,
+            conflicts_with = "percentages",
+            value_parser = clap::value_parser!(u8).range(..=20)
+        )]
+        steps: u8,
+
+        /// The percentages of the working weight to warm up at instead, e.g. `50,70,85`.
+        #[arg(long, value_delimiter = ',')]
+        percentages: Vec<f64>,
+    },
+    /// Drop down from a top set, pulling only the outermost plate off for each drop.
+    DropSet {
+        /// The top set, e.g. `100b`.
+        #[arg(value_parser = clap::value_parser!(Requirement))]
+        requirement: Requirement,
+
+        /// How many times to drop, as evenly as the plates allow.
+        #[arg(long, default_value_t = 3, conflicts_with = "percentage")]
+        drops: usize,
+
+        /// The percentage of the weight to take off for each drop instead, e.g. `20`.
+        #[arg(long)]
+        percentage: Option<f64>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum StateCommand {
+    /// Print what is saved as loaded on the bars.
+    Show,
+    /// Save every bar as bare.
+    Reset,
+    /// Save the given loadings, e.g. `b:20,10 d:2.5`, with every other bar bare.
+    Set {
+        #[arg(value_parser = clap::value_parser!(BarLoading))]
+        loadings: Vec<BarLoading>,
+    },
+}
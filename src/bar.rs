@@ -1,11 +1,19 @@
-use std::fmt::Display;
+use std::{fmt::Display, sync::Arc};
 
-use crate::bar_kind::BarKind;
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+use crate::{
+    bar_kind::BarKind,
+    collar::Collar,
+    unit::{Unit, UnitDisplay, Weight},
+};
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Bar {
     weight: u32,
     gauge: u32,
     kind: BarKind,
+    collar: Option<Collar>,
+    id: usize,
+    name: Option<Arc<str>>,
 }
 
 impl Bar {
@@ -15,6 +23,33 @@ impl Bar {
             weight,
             gauge,
             kind,
+            collar: None,
+            id: 0,
+            name: None,
+        }
+    }
+
+    /// Tells apart otherwise identical bars, so that each is loaded separately.
+    #[must_use]
+    pub fn with_id(self, id: usize) -> Self {
+        Bar { id, ..self }
+    }
+
+    #[must_use]
+    pub fn with_name(self, name: &str) -> Self {
+        Bar {
+            name: Some(name.into()),
+            ..self
+        }
+    }
+
+    /// Requires `collar` to be fitted outside the plates on each side whenever the bar is
+    /// loaded.
+    #[must_use]
+    pub fn with_collar(self, collar: Collar) -> Self {
+        Bar {
+            collar: Some(collar),
+            ..self
         }
     }
 
@@ -32,10 +67,54 @@ impl Bar {
     pub fn kind(&self) -> &BarKind {
         &self.kind
     }
+
+    #[must_use]
+    pub fn collar(&self) -> Option<&Collar> {
+        self.collar.as_ref()
+    }
+
+    #[must_use]
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    #[must_use]
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Whether both bars are the same make, regardless of which one of them they are.
+    #[must_use]
+    pub fn identical(&self, other: &Self) -> bool {
+        self.weight == other.weight
+            && self.gauge == other.gauge
+            && self.kind == other.kind
+            && self.collar == other.collar
+    }
+}
+
+impl UnitDisplay for Bar {
+    fn fmt_in(&self, f: &mut std::fmt::Formatter<'_>, unit: Unit) -> std::fmt::Result {
+        if let Some(name) = &self.name {
+            return write!(f, "{name}");
+        }
+
+        if self.kind.loadable() {
+            write!(f, "{} ({})", self.kind, self.gauge)?;
+        } else {
+            write!(f, "{} ({})", self.kind, Weight(self.weight).in_unit(unit))?;
+        }
+
+        if self.id > 0 {
+            write!(f, " #{}", self.id + 1)?;
+        }
+
+        Ok(())
+    }
 }
 
 impl Display for Bar {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{} ({})", self.kind, self.gauge)
+        self.fmt_in(f, Unit::Kg)
     }
 }
@@ -4,16 +4,50 @@ use core::{fmt::Display, str::FromStr};
 pub enum BarKind {
     Dumbbell,
     Barbell,
+    EzBar,
+    TrapBar,
+    Fixed,
 }
 
 impl BarKind {
+    /// How many plates of a kind have to go on a single handle for each plate in a loading.
     #[must_use]
     pub fn required_similar_plates(&self) -> usize {
         match self {
-            BarKind::Dumbbell => 4,
-            BarKind::Barbell => 2,
+            BarKind::Dumbbell | BarKind::Barbell | BarKind::EzBar | BarKind::TrapBar => 2,
+            BarKind::Fixed => 0,
         }
     }
+
+    /// How many matching handles make up one bar of this kind. Fixed-weight implements are
+    /// single, so a pair of them, e.g. two 16kg kettlebells, is asked for as a superset of the
+    /// two, `16f+16f`, rather than as a pair of dumbbells.
+    #[must_use]
+    pub fn handles(&self) -> usize {
+        match self {
+            BarKind::Dumbbell => 2,
+            BarKind::Barbell | BarKind::EzBar | BarKind::TrapBar | BarKind::Fixed => 1,
+        }
+    }
+
+    /// The letter bars of this kind are referred to by on the command line.
+    #[must_use]
+    pub fn letter(&self) -> char {
+        match self {
+            BarKind::Dumbbell => 'd',
+            BarKind::Barbell => 'b',
+            BarKind::EzBar => 'e',
+            BarKind::TrapBar => 't',
+            BarKind::Fixed => 'f',
+        }
+    }
+
+    /// Whether plates can be put on bars of this kind, as opposed to fixed-weight implements
+    /// like kettlebells.
+    #[must_use]
+    pub fn loadable(&self) -> bool {
+        self.required_similar_plates() > 0
+    }
 }
 
 impl Display for BarKind {
@@ -21,6 +55,9 @@ impl Display for BarKind {
         match self {
             BarKind::Dumbbell => write!(f, "Dumbbell"),
             BarKind::Barbell => write!(f, "Barbell"),
+            BarKind::EzBar => write!(f, "EZ Bar"),
+            BarKind::TrapBar => write!(f, "Trap Bar"),
+            BarKind::Fixed => write!(f, "Fixed"),
         }
     }
 }
@@ -32,6 +69,9 @@ impl FromStr for BarKind {
         match s {
             "d" => Ok(BarKind::Dumbbell),
             "b" => Ok(BarKind::Barbell),
+            "e" => Ok(BarKind::EzBar),
+            "t" => Ok(BarKind::TrapBar),
+            "f" => Ok(BarKind::Fixed),
             _ => Err("Invalid bar kind.".to_string()),
         }
     }
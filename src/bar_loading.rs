@@ -0,0 +1,168 @@
+use std::{fmt::Display, str::FromStr};
+
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bar::Bar,
+    bar_kind::BarKind,
+    dumbbell::Dumbbell,
+    plate::Plate,
+    unit::{Unit, UnitDisplay},
+};
+
+/// The plates on one side of a bar, picked out by its name or by its kind letter and position
+/// among the bars of that kind, e.g. `b:20,10`, `d2:2.5` or `blue handle:1.25`, in kg unless
+/// they say otherwise, e.g. `b:45lb`. A kind letter of `s` loads only one handle of a dumbbell,
+/// as does `s:` in front of a name, e.g. `s:blue handle:1.25`, and nothing after the colon
+/// leaves the bar bare.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BarLoading {
+    bar: String,
+    plates: Vec<u32>,
+}
+
+impl BarLoading {
+    #[must_use]
+    pub fn new(bar: &str, plates: Vec<u32>) -> Self {
+        BarLoading {
+            bar: bar.to_string(),
+            plates,
+        }
+    }
+
+    /// Describes `dumbbell` so that it resolves back to the same bar among `bars`.
+    #[must_use]
+    pub fn from_dumbbell(dumbbell: &Dumbbell, bars: &[Bar]) -> Self {
+        let bar = dumbbell.bar();
+
+        let single = dumbbell.handles() < bar.kind().handles();
+
+        let name = match (bar.name(), single) {
+            (Some(name), true) => format!("s:{name}"),
+            (Some(name), false) => name.to_string(),
+            (None, _) => {
+                let letter = if single { 's' } else { bar.kind().letter() };
+                let position = bars
+                    .iter()
+                    .filter(|other| other.kind() == bar.kind())
+                    .position(|other| other == bar)
+                    .unwrap_or_default();
+
+                format!("{letter}{}", position + 1)
+            }
+        };
+
+        BarLoading::new(&name, dumbbell.plates().iter().map(Plate::weight).collect())
+    }
+
+    #[must_use]
+    pub fn bar(&self) -> &str {
+        &self.bar
+    }
+
+    #[must_use]
+    pub fn plates(&self) -> &[u32] {
+        &self.plates
+    }
+
+    /// Finds the bar among `bars` and loads it.
+    ///
+    /// # Errors
+    /// If no bar matches.
+    ///
+    pub fn resolve(&self, bars: &[Bar]) -> Result<Dumbbell, String> {
+        let (name, single) = match self.bar.strip_prefix("s:") {
+            Some(name) => (name, true),
+            None => (self.bar.as_str(), false),
+        };
+
+        let (bar, handles) = match bars.iter().find(|bar| bar.name() == Some(name)) {
+            Some(bar) if single => (bar, 1),
+            Some(bar) => (bar, bar.kind().handles()),
+            None => self.find_by_kind(bars)?,
+        };
+
+        Ok(Dumbbell::with_handles(
+            Plate::from_weights(self.plates.clone(), bar.gauge()),
+            bar.clone(),
+            handles,
+        ))
+    }
+
+    fn find_by_kind<'a>(&self, bars: &'a [Bar]) -> Result<(&'a Bar, usize), String> {
+        let unknown = || format!("Unknown bar {}.", self.bar);
+
+        let Some(letter) = self.bar.get(..1) else {
+            return Err(unknown());
+        };
+
+        let position = match &self.bar[1..] {
+            "" => 1,
+            position => position.parse::<usize>().map_err(|_| unknown())?,
+        };
+
+        let (bar_kind, handles) = match letter.to_lowercase().as_str() {
+            "s" => (BarKind::Dumbbell, 1),
+            letter => {
+                let bar_kind = BarKind::from_str(letter).map_err(|_| unknown())?;
+                (bar_kind, bar_kind.handles())
+            }
+        };
+
+        bars.iter()
+            .filter(|bar| *bar.kind() == bar_kind)
+            .nth(position.checked_sub(1).ok_or_else(unknown)?)
+            .map(|bar| (bar, handles))
+            .ok_or_else(unknown)
+    }
+}
+
+impl FromStr for BarLoading {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (bar, plates) = s
+            .rsplit_once(':')
+            .ok_or_else(|| "Expected a bar and its plates, e.g. b:20,10".to_string())?;
+
+        let plates = plates
+            .split(',')
+            .map(str::trim)
+            .filter(|plate| !plate.is_empty())
+            .map(|plate| {
+                Unit::parse_weight(plate, Unit::Kg).map_err(|_| "Invalid plate weight".to_string())
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(BarLoading::new(bar.trim(), plates))
+    }
+}
+
+/// Plates are shown without a unit in kg, and with it otherwise, e.g. `b:45lb`, so that the
+/// loading parses back the same way.
+impl UnitDisplay for BarLoading {
+    fn fmt_in(&self, f: &mut std::fmt::Formatter<'_>, unit: Unit) -> std::fmt::Result {
+        let suffix = if unit == Unit::Kg {
+            String::new()
+        } else {
+            unit.to_string()
+        };
+
+        write!(
+            f,
+            "{}:{}",
+            self.bar,
+            self.plates
+                .iter()
+                .map(|p| format!("{}{suffix}", unit.value(*p)))
+                .join(",")
+        )
+    }
+}
+
+impl Display for BarLoading {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.fmt_in(f, Unit::Kg)
+    }
+}
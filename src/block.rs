@@ -0,0 +1,84 @@
+use std::{fmt::Display, str::FromStr};
+
+use itertools::Itertools;
+
+use crate::{
+    requirement::Requirement,
+    superset::Superset,
+    unit::{Unit, UnitDisplay},
+};
+
+/// Supersets that can be done in any order among themselves, while blocks are done in the
+/// order they are given. A block of a single superset keeps it in place.
+#[derive(Clone, Debug)]
+pub struct Block {
+    supersets: Vec<Superset>,
+}
+
+impl Block {
+    #[must_use]
+    pub fn new(supersets: Vec<Superset>) -> Self {
+        Block { supersets }
+    }
+
+    #[must_use]
+    pub fn supersets(&self) -> &[Superset] {
+        &self.supersets
+    }
+
+    /// Splits the supersets into their sets. The sets of a lone superset are done one after
+    /// the other, while those of a block of several can be done in any order like the
+    /// supersets themselves.
+    #[must_use]
+    pub fn sets(&self) -> Vec<Block> {
+        match self.supersets.as_slice() {
+            [superset] => superset.sets().into_iter().map(Block::from).collect(),
+            supersets => vec![Block::new(
+                supersets.iter().flat_map(Superset::sets).collect(),
+            )],
+        }
+    }
+}
+
+impl From<Requirement> for Block {
+    fn from(requirement: Requirement) -> Self {
+        Block::new(vec![requirement.into()])
+    }
+}
+
+impl From<Superset> for Block {
+    fn from(superset: Superset) -> Self {
+        Block::new(vec![superset])
+    }
+}
+
+/// Parses supersets joined by commas, e.g. `10d,12d+20b`.
+impl FromStr for Block {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(',')
+            .map(|superset| superset.trim().parse())
+            .collect::<Result<Vec<_>, _>>()
+            .map(Block::new)
+    }
+}
+
+impl UnitDisplay for Block {
+    fn fmt_in(&self, f: &mut std::fmt::Formatter<'_>, unit: Unit) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            self.supersets
+                .iter()
+                .map(|superset| superset.in_unit(unit))
+                .join(", ")
+        )
+    }
+}
+
+impl Display for Block {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.fmt_in(f, Unit::Kg)
+    }
+}
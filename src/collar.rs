@@ -0,0 +1,22 @@
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Collar {
+    weight: u32,
+    gauge: u32,
+}
+
+impl Collar {
+    #[must_use]
+    pub fn new(weight: u32, gauge: u32) -> Self {
+        Collar { weight, gauge }
+    }
+
+    #[must_use]
+    pub fn weight(&self) -> u32 {
+        self.weight
+    }
+
+    #[must_use]
+    pub fn gauge(&self) -> u32 {
+        self.gauge
+    }
+}
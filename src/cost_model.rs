@@ -0,0 +1,98 @@
+use core::{fmt::Display, str::FromStr};
+
+use crate::{bar::Bar, collar::Collar, plate::Plate};
+
+/// How much effort it takes to put a plate or collar on or take it off a single handle, used to
+/// weigh the edges between gym states.
+pub trait CostModel {
+    fn plate_cost(&self, bar: &Bar, plate: &Plate) -> u32;
+
+    fn collar_cost(&self, bar: &Bar, collar: &Collar) -> u32;
+}
+
+/// Every plate change costs the same.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct UnitCost;
+
+impl CostModel for UnitCost {
+    fn plate_cost(&self, _bar: &Bar, _plate: &Plate) -> u32 {
+        1
+    }
+
+    fn collar_cost(&self, _bar: &Bar, _collar: &Collar) -> u32 {
+        1
+    }
+}
+
+/// A plate change costs the total weight moved, in grams, across every plate and collar that
+/// has to be handled.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PlateWeightCost;
+
+impl CostModel for PlateWeightCost {
+    fn plate_cost(&self, bar: &Bar, plate: &Plate) -> u32 {
+        plate.weight() * PlateCountCost.plate_cost(bar, plate)
+    }
+
+    fn collar_cost(&self, bar: &Bar, collar: &Collar) -> u32 {
+        collar.weight() * PlateCountCost.collar_cost(bar, collar)
+    }
+}
+
+/// A plate change costs the number of physical plates and collars that have to be handled.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PlateCountCost;
+
+impl CostModel for PlateCountCost {
+    #[allow(clippy::cast_possible_truncation)]
+    fn plate_cost(&self, bar: &Bar, _plate: &Plate) -> u32 {
+        bar.kind().required_similar_plates() as u32
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn collar_cost(&self, bar: &Bar, _collar: &Collar) -> u32 {
+        bar.kind().required_similar_plates() as u32
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum CostModelKind {
+    #[default]
+    Unit,
+    PlateWeight,
+    PlateCount,
+}
+
+impl CostModelKind {
+    #[must_use]
+    pub fn model(&self) -> Box<dyn CostModel> {
+        match self {
+            CostModelKind::Unit => Box::new(UnitCost),
+            CostModelKind::PlateWeight => Box::new(PlateWeightCost),
+            CostModelKind::PlateCount => Box::new(PlateCountCost),
+        }
+    }
+}
+
+impl Display for CostModelKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CostModelKind::Unit => write!(f, "unit"),
+            CostModelKind::PlateWeight => write!(f, "weight"),
+            CostModelKind::PlateCount => write!(f, "count"),
+        }
+    }
+}
+
+impl FromStr for CostModelKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "unit" => Ok(CostModelKind::Unit),
+            "weight" => Ok(CostModelKind::PlateWeight),
+            "count" => Ok(CostModelKind::PlateCount),
+            _ => Err("Invalid cost model.".to_string()),
+        }
+    }
+}
@@ -1,33 +1,56 @@
-use std::{fmt::Display, hash::Hash, rc::Rc};
+use std::{collections::HashMap, fmt::Display, hash::Hash, rc::Rc};
 
 use itertools::Itertools;
 
-use crate::{bar::Bar, plate::Plate};
+use crate::{
+    bar::Bar,
+    collar::Collar,
+    cost_model::CostModel,
+    plate::Plate,
+    unit::{Unit, UnitDisplay, Weight},
+};
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 
 pub struct Dumbbell {
     plates: Vec<Plate>,
     bar: Bar,
+    handles: usize,
 }
 
 impl Dumbbell {
     #[must_use]
     pub fn new(plates: Vec<Plate>, bar: Bar) -> Self {
+        let handles = bar.kind().handles();
+        Dumbbell::with_handles(plates, bar, handles)
+    }
+
+    /// Loads `plates` on only `handles` of the bar's handles, leaving the others bare.
+    #[must_use]
+    pub fn with_handles(plates: Vec<Plate>, bar: Bar, handles: usize) -> Self {
+        let plates: Vec<Plate> = plates
+            .into_iter()
+            .sorted()
+            .rev()
+            .filter(|p| p.gauge() == bar.gauge())
+            .collect();
+
+        let handles = if plates.is_empty() {
+            bar.kind().handles()
+        } else {
+            handles.clamp(1, bar.kind().handles())
+        };
+
         Dumbbell {
-            plates: plates
-                .into_iter()
-                .sorted()
-                .rev()
-                .filter(|p| p.gauge() == bar.gauge())
-                .collect(),
+            plates,
             bar,
+            handles,
         }
     }
 
     #[must_use]
     pub fn new_rc(plates: Vec<Plate>, bar: &Bar) -> Rc<Self> {
-        Rc::new(Dumbbell::new(plates, *bar))
+        Rc::new(Dumbbell::new(plates, bar.clone()))
     }
 
     #[must_use]
@@ -40,25 +63,137 @@ impl Dumbbell {
         &self.bar
     }
 
+    /// How many of the bar's handles carry the plates.
+    #[must_use]
+    pub fn handles(&self) -> usize {
+        self.handles
+    }
+
+    /// The weight of each loaded handle.
     #[must_use]
     pub fn weight(&self) -> u32 {
-        self.bar.weight() + self.plates.iter().map(Plate::weight).sum::<u32>() * 2
+        self.handle_weight(0)
+    }
+
+    /// How many of each plate this loading takes out of the inventory, across every handle.
+    #[must_use]
+    pub fn plate_usage(&self) -> HashMap<Plate, usize> {
+        self.plates.iter().fold(HashMap::new(), |mut acc, plate| {
+            *acc.entry(*plate).or_default() +=
+                self.bar.kind().required_similar_plates() * self.handles;
+            acc
+        })
     }
 
+    /// Whether `other` can be reached with a single move: adding, removing or replacing one
+    /// plate on every loaded handle, sliding any plates outside of it off and back on, or
+    /// loading or stripping the remaining handles to switch between a pair and a single.
     #[must_use]
     pub fn adjacent(&self, other: &Self) -> bool {
         if self.bar != other.bar {
             return false;
         }
 
-        if self.plates.len().abs_diff(other.plates.len()) != 1 {
-            return false;
+        if self.handles != other.handles {
+            return self.plates == other.plates;
+        }
+
+        match Self::divergence(&self.plates, &other.plates) {
+            ([_, removed @ ..], [_, added @ ..]) if removed == added => true,
+            ([_, removed @ ..], added) if removed == added => true,
+            (removed, [_, added @ ..]) if removed == added => true,
+            _ => false,
+        }
+    }
+
+    /// The cost of the single move from this loading to `other`, counting every outer plate
+    /// and collar that has to come off and go back on around the plate being changed, on every
+    /// handle.
+    #[must_use]
+    pub fn change_cost(&self, other: &Self, cost_model: &dyn CostModel) -> Option<u32> {
+        if !self.adjacent(other) {
+            return None;
+        }
+
+        Some(
+            (0..self.bar.kind().handles())
+                .map(|handle| {
+                    self.handle_change_cost(self.handle(handle), other.handle(handle), cost_model)
+                })
+                .sum(),
+        )
+    }
+
+    fn handle_change_cost(&self, from: &[Plate], to: &[Plate], cost_model: &dyn CostModel) -> u32 {
+        let (removed, added) = Self::divergence(from, to);
+
+        if removed.is_empty() && added.is_empty() {
+            return 0;
         }
 
-        self.plates
+        let collars = self.bar.collar().map_or(0, |collar| {
+            let fitted = u32::from(!from.is_empty()) + u32::from(!to.is_empty());
+            fitted * cost_model.collar_cost(&self.bar, collar)
+        });
+
+        removed
             .iter()
-            .zip(&other.plates)
-            .all(|(p1, p2)| p1.weight() == p2.weight())
+            .chain(added)
+            .map(|plate| cost_model.plate_cost(&self.bar, plate))
+            .sum::<u32>()
+            + collars
+    }
+
+    /// The plates taken off and put on each handle to get from this loading to `other`, in the
+    /// order they would be handled.
+    #[must_use]
+    pub fn handle_changes(&self, other: &Self) -> Vec<(Vec<Plate>, Vec<Plate>)> {
+        (0..self.bar.kind().handles())
+            .map(|handle| {
+                let (removed, added) = Self::divergence(self.handle(handle), other.handle(handle));
+                (removed.iter().rev().copied().collect(), added.to_vec())
+            })
+            .collect()
+    }
+
+    /// Every loading passed through when putting this one together a plate at a time, from the
+    /// bare bar up to and including this one, each a single move from the last.
+    #[must_use]
+    pub fn prefixes(&self) -> Vec<Self> {
+        (0..=self.plates.len())
+            .map(|n| {
+                Dumbbell::with_handles(self.plates[..n].to_vec(), self.bar.clone(), self.handles)
+            })
+            .collect()
+    }
+
+    /// The plates on one side of the given handle.
+    #[must_use]
+    pub fn handle(&self, handle: usize) -> &[Plate] {
+        if handle < self.handles {
+            &self.plates
+        } else {
+            &[]
+        }
+    }
+
+    /// The weight of a handle, including its collars once it carries any plates.
+    fn handle_weight(&self, handle: usize) -> u32 {
+        let plates = self.handle(handle);
+        let collar = match plates {
+            [] => 0,
+            _ => self.bar.collar().map_or(0, Collar::weight),
+        };
+
+        self.bar.weight() + (plates.iter().map(Plate::weight).sum::<u32>() + collar) * 2
+    }
+
+    /// Splits both loadings at the innermost plate where they differ, returning the plates that
+    /// have to come off the first and go on to reach the second.
+    fn divergence<'a>(from: &'a [Plate], to: &'a [Plate]) -> (&'a [Plate], &'a [Plate]) {
+        let common = from.iter().zip(to).take_while(|(p1, p2)| p1 == p2).count();
+
+        (&from[common..], &to[common..])
     }
 }
 
@@ -74,19 +209,33 @@ impl Ord for Dumbbell {
     }
 }
 
+impl UnitDisplay for Dumbbell {
+    fn fmt_in(&self, f: &mut std::fmt::Formatter<'_>, unit: Unit) -> std::fmt::Result {
+        for handle in 0..self.bar.kind().handles() {
+            if handle > 0 {
+                write!(f, " + ")?;
+            }
+
+            let plates = self
+                .handle(handle)
+                .iter()
+                .map(|p| unit.value(p.weight()))
+                .collect::<Vec<_>>();
+
+            write!(
+                f,
+                "{:?} ({})",
+                plates,
+                Weight(self.handle_weight(handle)).in_unit(unit)
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
 impl Display for Dumbbell {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let kg_plates = self
-            .plates
-            .iter()
-            .map(|p| f64::from(p.weight()) / 1000.0)
-            .collect::<Vec<_>>();
-
-        write!(
-            f,
-            "{:?} ({}kg)",
-            kg_plates,
-            f64::from(self.weight()) / 1000.0,
-        )
+        self.fmt_in(f, Unit::Kg)
     }
 }
@@ -0,0 +1,297 @@
+use std::{collections::HashMap, rc::Rc};
+
+use itertools::Itertools;
+
+use crate::{
+    bar::Bar,
+    bar_kind::BarKind,
+    graph::{BarGraph, DistanceCache},
+    superset::Superset,
+};
+
+/// A lower bound on what is left to pay to get through a session from any point of the search,
+/// which steers it towards the goal without ever making it miss the cheapest plan. Every move
+/// changes a single bar, so bounds for different bars add up.
+pub struct Estimate {
+    chains: Vec<Chain>,
+    shared: Vec<Shared>,
+}
+
+/// A bar that is the only one of its kind, which has to pass through a loading for every block
+/// that needs its kind, in order.
+struct Chain {
+    bar: usize,
+    stages: Vec<Stage>,
+    strip_costs: Option<Vec<u32>>,
+}
+
+struct Stage {
+    block: usize,
+    supersets: Vec<usize>,
+    distances: Vec<u32>,
+    rest: u32,
+}
+
+/// Bars of a kind there are several of, which can take turns, so only the furthest any
+/// requirement is from all of them is counted.
+struct Shared {
+    bars: Vec<usize>,
+    targets: Vec<Target>,
+    strip_costs: Option<Vec<Vec<u32>>>,
+}
+
+struct Target {
+    block: usize,
+    superset: usize,
+    distances: Vec<Rc<[u32]>>,
+}
+
+impl Estimate {
+    /// Distances to the loadings meeting each requirement are taken from `cache`, and added to
+    /// it when missing.
+    #[must_use]
+    pub fn new(
+        graphs: &HashMap<Bar, BarGraph>,
+        cache: &mut DistanceCache,
+        bars: &[Bar],
+        blocks: &[Vec<Superset>],
+        strip: bool,
+    ) -> Self {
+        let mut chains = Vec::new();
+        let mut shared = Vec::new();
+
+        let kinds = bars
+            .iter()
+            .enumerate()
+            .into_group_map_by(|(_, bar)| *bar.kind());
+
+        for (bar_kind, kind_bars) in kinds.into_iter().sorted_by_key(|(bar_kind, _)| *bar_kind) {
+            let indices = kind_bars.iter().map(|(i, _)| *i).collect_vec();
+
+            match kind_bars.as_slice() {
+                [(i, bar)] => {
+                    chains.push(Chain::new(bar, &graphs[*bar], cache, *i, blocks, strip));
+                }
+                _ => shared.push(Shared::new(
+                    graphs, cache, bars, indices, bar_kind, blocks, strip,
+                )),
+            }
+        }
+
+        Estimate { chains, shared }
+    }
+
+    /// The bound from `loadings`, once the blocks before `progress` and the supersets of the
+    /// current block marked as `done` have been met.
+    #[must_use]
+    pub fn remaining(&self, progress: usize, done: &[bool], loadings: &[Option<usize>]) -> u32 {
+        let remaining = |block: usize, superset: usize| {
+            block > progress || (block == progress && !done.get(superset).copied().unwrap_or(true))
+        };
+
+        let chains = self.chains.iter().map(|chain| {
+            let loading = loadings[chain.bar];
+
+            match chain.stages.iter().find(|stage| {
+                stage
+                    .supersets
+                    .iter()
+                    .any(|superset| remaining(stage.block, *superset))
+            }) {
+                Some(stage) => loading
+                    .map_or(0, |loading| stage.distances[loading])
+                    .saturating_add(stage.rest),
+                None => loading
+                    .zip(chain.strip_costs.as_ref())
+                    .map_or(0, |(loading, strip_costs)| strip_costs[loading]),
+            }
+        });
+
+        let shared = self.shared.iter().map(|shared| {
+            let requirements = shared
+                .targets
+                .iter()
+                .filter(|target| remaining(target.block, target.superset))
+                .map(|target| {
+                    shared
+                        .bars
+                        .iter()
+                        .zip(&target.distances)
+                        .map(|(bar, distances)| {
+                            loadings[*bar].map_or(0, |loading| distances[loading])
+                        })
+                        .min()
+                        .unwrap_or_default()
+                })
+                .max()
+                .unwrap_or_default();
+
+            let stripping = shared.strip_costs.as_ref().map_or(0, |strip_costs| {
+                shared
+                    .bars
+                    .iter()
+                    .zip(strip_costs)
+                    .filter_map(|(bar, strip_costs)| Some(strip_costs[loadings[*bar]?]))
+                    .fold(0_u32, u32::saturating_add)
+            });
+
+            requirements.max(stripping)
+        });
+
+        chains.chain(shared).fold(0, u32::saturating_add)
+    }
+}
+
+impl Chain {
+    fn new(
+        kind_bar: &Bar,
+        graph: &BarGraph,
+        cache: &mut DistanceCache,
+        bar: usize,
+        blocks: &[Vec<Superset>],
+        strip: bool,
+    ) -> Self {
+        let bar_kind = *kind_bar.kind();
+        let strip_costs = strip.then(|| {
+            (0..graph.dumbbells().len())
+                .map(|loading| graph.strip_cost(loading))
+                .collect_vec()
+        });
+
+        let mut stages = blocks
+            .iter()
+            .enumerate()
+            .filter_map(|(block, supersets)| {
+                let requirements = supersets
+                    .iter()
+                    .flat_map(Superset::requirements)
+                    .filter(|requirement| requirement.bar_kind() == bar_kind)
+                    .collect_vec();
+
+                if requirements.is_empty() {
+                    return None;
+                }
+
+                Some(Stage {
+                    block,
+                    supersets: supersets
+                        .iter()
+                        .positions(|superset| {
+                            superset
+                                .requirements()
+                                .iter()
+                                .any(|requirement| requirement.bar_kind() == bar_kind)
+                        })
+                        .collect(),
+                    distances: requirements
+                        .iter()
+                        .map(|requirement| cache.distances(kind_bar, graph, requirement))
+                        .fold(
+                            vec![u32::MAX; graph.dumbbells().len()],
+                            |closest, distances| {
+                                closest
+                                    .iter()
+                                    .zip(distances.iter())
+                                    .map(|(closest, distance)| *closest.min(distance))
+                                    .collect()
+                            },
+                        ),
+                    rest: 0,
+                })
+            })
+            .collect_vec();
+
+        let mut rest = strip_costs.as_ref().map_or(0, |strip_costs| {
+            stages.last().map_or(0, |stage| {
+                Self::closest(&stage.distances, |loading| strip_costs[loading])
+            })
+        });
+
+        for i in (0..stages.len()).rev() {
+            stages[i].rest = rest;
+
+            if i > 0 {
+                let next = &stages[i].distances;
+                rest = rest.saturating_add(Self::closest(&stages[i - 1].distances, |loading| {
+                    next[loading]
+                }));
+            }
+        }
+
+        Chain {
+            bar,
+            stages,
+            strip_costs,
+        }
+    }
+
+    /// The least `cost` of any loading in a stage.
+    fn closest(stage: &[u32], cost: impl Fn(usize) -> u32) -> u32 {
+        stage
+            .iter()
+            .positions(|distance| *distance == 0)
+            .map(cost)
+            .min()
+            .unwrap_or(u32::MAX)
+    }
+}
+
+impl Shared {
+    fn new(
+        graphs: &HashMap<Bar, BarGraph>,
+        cache: &mut DistanceCache,
+        bars: &[Bar],
+        indices: Vec<usize>,
+        bar_kind: BarKind,
+        blocks: &[Vec<Superset>],
+        strip: bool,
+    ) -> Self {
+        let kind_graphs = indices
+            .iter()
+            .map(|i| (&bars[*i], &graphs[&bars[*i]]))
+            .collect_vec();
+
+        let targets = blocks
+            .iter()
+            .enumerate()
+            .flat_map(|(block, supersets)| {
+                supersets
+                    .iter()
+                    .enumerate()
+                    .map(move |(superset, requirements)| (block, superset, requirements))
+            })
+            .flat_map(|(block, superset, requirements)| {
+                requirements
+                    .requirements()
+                    .iter()
+                    .filter(|requirement| requirement.bar_kind() == bar_kind)
+                    .map(move |requirement| (block, superset, requirement))
+            })
+            .map(|(block, superset, requirement)| Target {
+                block,
+                superset,
+                distances: kind_graphs
+                    .iter()
+                    .map(|(bar, graph)| cache.distances(bar, graph, requirement))
+                    .collect(),
+            })
+            .collect();
+
+        let strip_costs = strip.then(|| {
+            kind_graphs
+                .iter()
+                .map(|(_, graph)| {
+                    (0..graph.dumbbells().len())
+                        .map(|loading| graph.strip_cost(loading))
+                        .collect()
+                })
+                .collect()
+        });
+
+        Shared {
+            bars: indices,
+            targets,
+            strip_costs,
+        }
+    }
+}
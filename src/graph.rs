@@ -1 +1,164 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+    rc::Rc,
+};
 
+use itertools::Itertools;
+
+use crate::{
+    bar::Bar, bar_kind::BarKind, cost_model::CostModel, dumbbell::Dumbbell, plate::Plate,
+    requirement::Requirement,
+};
+
+/// Everything that decides which loadings meet a requirement: its bar kind, how many handles
+/// it needs and the weights it accepts.
+type Target = (BarKind, usize, (u32, u32));
+
+/// Every loading of a single bar along with the single moves between them, weighed by a cost
+/// model. Gym states are searched lazily on top of these rather than enumerated up front.
+#[derive(Clone, Debug)]
+pub struct BarGraph {
+    dumbbells: Vec<Dumbbell>,
+    usages: Vec<HashMap<Plate, usize>>,
+    edges: Vec<Vec<(usize, u32)>>,
+    strip_costs: Vec<u32>,
+}
+
+impl BarGraph {
+    #[must_use]
+    pub fn new(dumbbells: Vec<Dumbbell>, cost_model: &dyn CostModel) -> Self {
+        let mut edges = vec![Vec::new(); dumbbells.len()];
+
+        for ((i1, dumbbell1), (i2, dumbbell2)) in dumbbells.iter().enumerate().tuple_combinations()
+        {
+            if let Some(cost) = dumbbell1.change_cost(dumbbell2, cost_model) {
+                edges[i1].push((i2, cost));
+                edges[i2].push((i1, cost));
+            }
+        }
+
+        let usages = dumbbells.iter().map(Dumbbell::plate_usage).collect();
+
+        let mut graph = BarGraph {
+            dumbbells,
+            usages,
+            edges,
+            strip_costs: Vec::new(),
+        };
+        graph.strip_costs = graph.distances_to(|dumbbell| dumbbell.plates().is_empty());
+
+        graph
+    }
+
+    #[must_use]
+    pub fn dumbbells(&self) -> &[Dumbbell] {
+        &self.dumbbells
+    }
+
+    #[must_use]
+    pub fn dumbbell(&self, loading: usize) -> &Dumbbell {
+        &self.dumbbells[loading]
+    }
+
+    #[must_use]
+    pub fn usage(&self, loading: usize) -> &HashMap<Plate, usize> {
+        &self.usages[loading]
+    }
+
+    /// The cost of taking every plate off `loading`.
+    #[must_use]
+    pub fn strip_cost(&self, loading: usize) -> u32 {
+        self.strip_costs[loading]
+    }
+
+    /// The loading with no plates on.
+    #[must_use]
+    pub fn bare(&self) -> Option<usize> {
+        self.dumbbells
+            .iter()
+            .position(|dumbbell| dumbbell.plates().is_empty())
+    }
+
+    /// The cheapest way from each loading to any loading matching `target`, or `u32::MAX` if
+    /// there is none.
+    #[must_use]
+    pub fn distances_to(&self, target: impl Fn(&Dumbbell) -> bool) -> Vec<u32> {
+        let mut distances = vec![u32::MAX; self.dumbbells.len()];
+        let mut frontier = BinaryHeap::new();
+
+        for (i, dumbbell) in self.dumbbells.iter().enumerate() {
+            if target(dumbbell) {
+                distances[i] = 0;
+                frontier.push(Reverse((0, i)));
+            }
+        }
+
+        while let Some(Reverse((distance, i))) = frontier.pop() {
+            if distance > distances[i] {
+                continue;
+            }
+
+            for &(next, cost) in &self.edges[i] {
+                let total = distance + cost;
+                if total < distances[next] {
+                    distances[next] = total;
+                    frontier.push(Reverse((total, next)));
+                }
+            }
+        }
+
+        distances
+    }
+
+    /// The loadings reachable from `loading` with a single move, and what each move costs.
+    #[must_use]
+    pub fn edges(&self, loading: usize) -> &[(usize, u32)] {
+        &self.edges[loading]
+    }
+}
+
+/// The distances from every loading of a bar to those meeting a requirement, kept across calls
+/// so that the sets of a requirement, and sessions planned again on the same gym, only search
+/// each bar once per requirement.
+#[derive(Clone, Debug, Default)]
+pub struct DistanceCache {
+    distances: HashMap<(Bar, Target), Rc<[u32]>>,
+}
+
+impl DistanceCache {
+    /// The cheapest way from each loading of `bar` to any loading meeting `requirement`, as
+    /// [`BarGraph::distances_to`] gives it.
+    pub fn distances(
+        &mut self,
+        bar: &Bar,
+        graph: &BarGraph,
+        requirement: &Requirement,
+    ) -> Rc<[u32]> {
+        let target = (
+            requirement.bar_kind(),
+            requirement.handles(),
+            requirement.bounds(),
+        );
+
+        self.distances
+            .entry((bar.clone(), target))
+            .or_insert_with(|| {
+                graph
+                    .distances_to(|dumbbell| requirement.matches(dumbbell))
+                    .into()
+            })
+            .clone()
+    }
+
+    /// How many bar and requirement pairs have their distances kept.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.distances.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.distances.is_empty()
+    }
+}
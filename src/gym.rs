@@ -1,320 +1,914 @@
-use std::collections::{HashMap, HashSet};
+use std::{
+    cell::RefCell,
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet},
+};
 
 use itertools::Itertools;
-use petgraph::{algo, prelude::UnGraphMap};
 
 use crate::{
     bar::Bar,
     bar_kind::BarKind,
+    block::Block,
+    cost_model::CostModel,
     dumbbell::Dumbbell,
+    estimate::Estimate,
+    graph::{BarGraph, DistanceCache},
     gym_error::GymError,
-    gym_state::{GymState, GymStateId},
+    gym_state::GymState,
+    plan::{Plan, Step},
     plate::Plate,
     requirement::Requirement,
+    rounding::Rounding,
+    superset::Superset,
 };
 
+/// The loading index of every bar being searched over, or `None` for bars that haven't been used
+/// yet and can be set up freely the first time they are needed.
+type Loadings = Vec<Option<usize>>;
+
+/// A superset along with the bar each of its requirements was met on and the loadings in place.
+type Met = (Superset, Vec<Bar>, Loadings);
+
+/// Where the search is up to: the loadings in place, the block being worked through, and which
+/// of that block's supersets have been met.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct SearchNode {
+    loadings: Loadings,
+    progress: usize,
+    done: Vec<bool>,
+}
+
 pub struct Gym {
-    states: HashMap<BarKind, Vec<GymState>>,
-    graphs: HashMap<BarKind, UnGraphMap<GymStateId, u32>>,
+    plates: HashMap<Plate, usize>,
+    graphs: HashMap<Bar, BarGraph>,
+    distances: RefCell<DistanceCache>,
     weights: HashMap<Bar, Vec<u32>>,
+    single_weights: HashMap<Bar, Vec<u32>>,
     bar_options: HashMap<BarKind, Vec<Bar>>,
+    rounding: Rounding,
 }
 
 impl Gym {
     #[must_use]
-    pub fn new(plates: &HashMap<Plate, usize>, bars: &[Bar]) -> Self {
-        let dumbbells: HashMap<Bar, Vec<Dumbbell>> = bars
+    pub fn new(plates: &HashMap<Plate, usize>, bars: &[Bar], cost_model: &dyn CostModel) -> Self {
+        let graphs: HashMap<Bar, BarGraph> = bars
             .iter()
-            .map(|bar| (*bar, Self::dumbbells(plates, bar)))
-            .collect();
-
-        let weights = dumbbells
-            .iter()
-            .map(|(bar, dumbbells)| {
-                let weights = dumbbells
-                    .iter()
-                    .map(Dumbbell::weight)
-                    .sorted()
-                    .dedup()
-                    .collect();
-                (*bar, weights)
-            })
-            .collect();
-
-        let dumbbells: HashMap<BarKind, HashMap<Bar, Vec<Dumbbell>>> =
-            dumbbells
-                .into_iter()
-                .fold(HashMap::new(), |mut acc, (bar, dumbbells)| {
-                    acc.entry(*bar.kind()).or_default().insert(bar, dumbbells);
-                    acc
-                });
-
-        let states: HashMap<BarKind, Vec<GymState>> = dumbbells
-            .into_iter()
-            .map(|(kind, dumbbells)| {
-                let states = dumbbells
-                    .into_values()
-                    .multi_cartesian_product()
-                    .map(|dumbbells| {
-                        GymState::new(
-                            dumbbells
-                                .into_iter()
-                                .map(|dumbbell| (*dumbbell.bar(), dumbbell))
-                                .collect::<HashMap<_, _>>(),
-                        )
-                    })
-                    .collect();
-                (kind, states)
+            .map(|bar| {
+                (
+                    bar.clone(),
+                    BarGraph::new(Self::dumbbells(plates, bar), cost_model),
+                )
             })
             .collect();
 
-        let ids = states
-            .iter()
-            .enumerate()
-            .map(|(i, _)| GymStateId(i))
-            .collect::<HashSet<_>>();
-
-        let graphs = states
-            .iter()
-            .map(|(kind, states)| {
-                let graph = Self::graph(states, &ids);
-                (*kind, graph)
-            })
-            .collect();
+        let weights_with = |single: bool| -> HashMap<Bar, Vec<u32>> {
+            graphs
+                .iter()
+                .filter_map(|(bar, graph)| {
+                    let weights = graph
+                        .dumbbells()
+                        .iter()
+                        .filter(|dumbbell| single || dumbbell.handles() == bar.kind().handles())
+                        .map(Dumbbell::weight)
+                        .sorted()
+                        .dedup()
+                        .collect_vec();
+                    (!single || bar.kind().handles() > 1).then(|| (bar.clone(), weights))
+                })
+                .collect()
+        };
+        let weights = weights_with(false);
+        let single_weights = weights_with(true);
 
         let bar_options: HashMap<BarKind, Vec<Bar>> =
             bars.iter().fold(HashMap::new(), |mut acc, bar| {
-                acc.entry(*bar.kind()).or_default().push(*bar);
+                acc.entry(*bar.kind()).or_default().push(bar.clone());
                 acc
             });
 
         Gym {
-            states,
+            plates: plates.clone(),
             graphs,
+            distances: RefCell::default(),
             weights,
+            single_weights,
             bar_options,
+            rounding: Rounding::Exact,
         }
     }
 
+    /// Rounds requirements that can't be built exactly and don't say how to round themselves
+    /// as `rounding` says.
+    #[must_use]
+    pub fn with_rounding(self, rounding: Rounding) -> Self {
+        Gym { rounding, ..self }
+    }
+
+    /// Plans the requirements in session order, with the state of every bar after each one and
+    /// the plate changes made to reach it. Bars can be set up however suits the first
+    /// requirement they are needed for. Weights that can't be built exactly are rounded as set
+    /// with [`Gym::with_rounding`], unless a requirement says otherwise.
     ///
     /// # Errors
     /// If it is impossible to construct a dumbbell for a requirement given the user's plates.
     ///
-    pub fn order(
-        &self,
-        requirements: &[Requirement],
-    ) -> Result<HashMap<Bar, Vec<&Dumbbell>>, GymError> {
-        let requirements_by_kind: HashMap<BarKind, Vec<Requirement>> =
-            requirements.iter().fold(HashMap::new(), |mut acc, req| {
-                acc.entry(req.bar_kind()).or_default().push(*req);
-                acc
-            });
-
-        let mut result = HashMap::<Bar, Vec<&Dumbbell>>::new();
+    pub fn order(&self, requirements: &[Requirement]) -> Result<Plan, GymError> {
+        let blocks = requirements.iter().cloned().map(Block::from).collect_vec();
 
-        for (bar_kind, reqs) in requirements_by_kind {
-            let ordered_dumbbells = self.order_by_kind(bar_kind, &reqs)?;
-            for (bar, dumbbells) in ordered_dumbbells {
-                result.entry(bar).or_default().extend(dumbbells);
-            }
-        }
-
-        Ok(result)
+        self.order_with(None, &blocks, false)
     }
 
+    /// Plans the blocks like [`Gym::order`], doing the supersets within each block in whichever
+    /// order is cheapest. Starts from the bars as they are loaded in `start`, with bars it leaves
+    /// out taken to be bare, and optionally strips every bar once the session is over. Both are
+    /// counted in the cost of the plan.
+    ///
+    /// Every bar that is needed, already loaded or to be stripped is searched over at once, so
+    /// the session keeps its order across bar kinds and all of them share the plates. Each set
+    /// of a requirement is planned as a step of its own.
     ///
     /// # Errors
-    /// If it is impossible to construct a dumbbell for a requirement given the user's plates.
+    /// If `start` can't be built from the user's plates, if it is impossible to construct a
+    /// dumbbell for a requirement or to load a superset at once, or if `strip` is set and the
+    /// bars can't all be stripped afterwards.
     ///
-    pub fn order_by_kind(
+    pub fn order_with(
         &self,
-        bar_kind: BarKind,
-        requirements: &[Requirement],
-    ) -> Result<HashMap<Bar, Vec<&Dumbbell>>, GymError> {
-        if requirements.is_empty() {
-            return Ok(HashMap::new());
+        start: Option<&GymState>,
+        blocks: &[Block],
+        strip: bool,
+    ) -> Result<Plan, GymError> {
+        if let Some(start) = start
+            && !Self::fits(&self.plates, &start.plate_usage())
+        {
+            return Err(GymError::InvalidStart(start.clone()));
         }
 
-        let optimal_sequence = self.find_optimal_sequence(bar_kind, requirements)?;
+        let needed = blocks
+            .iter()
+            .flat_map(Block::supersets)
+            .flat_map(Superset::requirements)
+            .map(Requirement::bar_kind)
+            .collect::<HashSet<_>>();
 
-        let mut result = HashMap::<Bar, Vec<&Dumbbell>>::new();
-        let mut requirement_index = 0;
+        let bars = self
+            .bar_options
+            .iter()
+            .sorted_by_key(|(bar_kind, _)| *bar_kind)
+            .flat_map(|(_, bars)| bars)
+            .filter(|bar| {
+                strip
+                    || needed.contains(bar.kind())
+                    || start
+                        .and_then(|start| start.get(bar))
+                        .is_some_and(|dumbbell| !dumbbell.plates().is_empty())
+            })
+            .cloned()
+            .collect_vec();
 
-        for state_id in optimal_sequence {
-            let state = &self.states[&bar_kind][state_id.0];
-            let bars = self
-                .bar_options
-                .get(&requirements[requirement_index].bar_kind())
-                .ok_or(GymError::InvalidRequirement(
-                    requirements[requirement_index],
-                ))?;
+        let blocks = blocks
+            .iter()
+            .flat_map(Block::sets)
+            .map(|block| {
+                block
+                    .supersets()
+                    .iter()
+                    .map(|superset| {
+                        Superset::new(
+                            superset
+                                .requirements()
+                                .iter()
+                                .map(|requirement| self.rounded(requirement.clone()))
+                                .collect(),
+                        )
+                        .with_set(superset.set())
+                    })
+                    .collect_vec()
+            })
+            .filter(|supersets| !supersets.is_empty())
+            .collect_vec();
 
-            for bar in bars {
-                if let Some(dumbbell) = state.get(bar) {
-                    if dumbbell.weight() == requirements[requirement_index].weight() {
-                        result.entry(*bar).or_default().push(dumbbell);
-                    }
-                }
-            }
+        let (sequence, cost) = self.find_optimal_sequence(&bars, &blocks, start, strip)?;
 
-            if requirement_index < requirements.len() - 1 {
-                requirement_index += 1;
-            }
-        }
+        Ok(self.plan(start, &bars, sequence, strip, cost))
+    }
 
-        Ok(result)
+    /// How many bar and requirement pairs the distances kept across plans are known for.
+    #[must_use]
+    pub fn cached_distances(&self) -> usize {
+        self.distances.borrow().len()
     }
 
+    /// The weights each bar can be loaded to with every one of its handles, e.g. a pair of
+    /// dumbbells.
     #[must_use]
     pub fn weights(&self) -> &HashMap<Bar, Vec<u32>> {
         &self.weights
     }
 
-    fn dumbbells(weights_map: &HashMap<Plate, usize>, bar: &Bar) -> Vec<Dumbbell> {
-        Self::available_dumbbells(
-            &weights_map
-                .iter()
-                .filter(|(_, count)| *count >= &bar.kind().required_similar_plates())
-                .map(|(plate, count)| (*plate, count / bar.kind().required_similar_plates()))
-                .flat_map(|(plate, count)| vec![plate; count])
-                .collect::<Vec<_>>(),
-            bar,
-        )
-        .into_iter()
-        .sorted()
-        .collect()
+    /// The weights a single handle of each bar can be loaded to, for the bars that have more
+    /// than one handle, whether or not the other handle is loaded the same.
+    #[must_use]
+    pub fn single_weights(&self) -> &HashMap<Bar, Vec<u32>> {
+        &self.single_weights
     }
 
-    fn available_dumbbells(plates: &[Plate], bar: &Bar) -> HashSet<Dumbbell> {
-        plates
+    /// Ramps up to the requirement with a warm-up set at each of `percentages` of the working
+    /// weight, each loaded by adding a single plate to the last, ending with the working
+    /// loading. The working loading is picked so that the warm-ups come as close to the
+    /// percentages as they can, and if it has too few plates for them all, the lightest ones
+    /// are left out.
+    ///
+    /// # Errors
+    /// If any of the percentages isn't between 0 and 100, or if it is impossible to construct a
+    /// dumbbell for the requirement given the user's plates.
+    ///
+    pub fn warm_up(
+        &self,
+        requirement: &Requirement,
+        percentages: &[f64],
+    ) -> Result<Vec<Dumbbell>, GymError> {
+        if let Some(percentage) = percentages
             .iter()
-            .powerset()
-            .map(|plates| Dumbbell::new(plates.into_iter().copied().collect(), *bar))
-            .collect::<HashSet<_>>()
+            .find(|percentage| !Self::valid_percentage(**percentage))
+        {
+            return Err(GymError::InvalidPercentage(*percentage));
+        }
+
+        let requirement = self.rounded(requirement.clone());
+        let percentages = percentages
+            .iter()
+            .copied()
+            .sorted_by(f64::total_cmp)
+            .collect_vec();
+
+        self.loadings(&requirement)
+            .map(|working| {
+                let prefixes = working.prefixes();
+                let ramp = prefixes[prefixes
+                    .len()
+                    .saturating_sub(percentages.len().saturating_add(1))..]
+                    .to_vec();
+                let error = ramp
+                    .iter()
+                    .rev()
+                    .skip(1)
+                    .zip(percentages.iter().rev())
+                    .map(|(dumbbell, percentage)| {
+                        let actual =
+                            f64::from(dumbbell.weight()) / f64::from(working.weight()) * 100.0;
+                        (actual - percentage).powi(2)
+                    })
+                    .sum::<f64>();
+
+                (ramp, error)
+            })
+            .min_by(|(ramp1, error1), (ramp2, error2)| {
+                ramp2.len().cmp(&ramp1.len()).then(error1.total_cmp(error2))
+            })
+            .map(|(ramp, _)| ramp)
+            .ok_or(GymError::InvalidRequirement(requirement))
     }
 
-    fn graph(states: &[GymState], ids: &HashSet<GymStateId>) -> UnGraphMap<GymStateId, u32> {
-        let mut graph = UnGraphMap::<GymStateId, u32>::new();
+    /// Ramps up to the requirement like [`Gym::warm_up`] with `steps` warm-up sets spread
+    /// evenly below the working weight.
+    ///
+    /// # Errors
+    /// If it is impossible to construct a dumbbell for the requirement given the user's plates.
+    ///
+    #[allow(clippy::cast_precision_loss)]
+    pub fn warm_up_steps(
+        &self,
+        requirement: &Requirement,
+        steps: usize,
+    ) -> Result<Vec<Dumbbell>, GymError> {
+        let percentages = (1..=steps)
+            .map(|step| 100.0 * step as f64 / steps.saturating_add(1) as f64)
+            .collect_vec();
+
+        self.warm_up(requirement, &percentages)
+    }
+
+    /// Drops down from the requirement `drops` times, pulling the outermost plate off for each
+    /// drop so that no other plates have to be moved, starting with the top loading. The top
+    /// loading is picked so that its smallest drop takes off as much as it can, then so that
+    /// the drops are as even as they can be, and if it has too few plates for them all, the
+    /// drops run out at the bare bar.
+    ///
+    /// # Errors
+    /// If it is impossible to construct a dumbbell for the requirement given the user's plates.
+    ///
+    #[allow(clippy::cast_precision_loss)]
+    pub fn drop_sets(
+        &self,
+        requirement: &Requirement,
+        drops: usize,
+    ) -> Result<Vec<Dumbbell>, GymError> {
+        let requirement = self.rounded(requirement.clone());
+
+        self.loadings(&requirement)
+            .map(|top| {
+                let prefixes = top.prefixes();
+                let sets = prefixes[prefixes.len().saturating_sub(drops.saturating_add(1))..]
+                    .iter()
+                    .rev()
+                    .cloned()
+                    .collect_vec();
+                let percentages = Self::drop_percentages(&sets);
+                let smallest = percentages.iter().copied().fold(f64::INFINITY, f64::min);
+                let mean = percentages.iter().sum::<f64>() / percentages.len().max(1) as f64;
+                let error = percentages
+                    .iter()
+                    .map(|percentage| (percentage - mean).powi(2))
+                    .sum::<f64>();
+
+                (sets, smallest, error)
+            })
+            .min_by(|(sets1, smallest1, error1), (sets2, smallest2, error2)| {
+                sets2
+                    .len()
+                    .cmp(&sets1.len())
+                    .then(smallest2.total_cmp(smallest1))
+                    .then(error1.total_cmp(error2))
+            })
+            .map(|(sets, _, _)| sets)
+            .ok_or(GymError::InvalidRequirement(requirement))
+    }
 
-        for id in ids {
-            graph.add_node(*id);
+    /// Drops down from the requirement like [`Gym::drop_sets`], taking as close to `percentage`
+    /// of the weight off for each drop as pulling outer plates allows, for as long as no drop
+    /// takes off more than twice as much. The top loading is picked to drop as many times as
+    /// possible, as close to `percentage` as possible.
+    ///
+    /// # Errors
+    /// If `percentage` isn't between 0 and 100, or if it is impossible to construct a dumbbell
+    /// for the requirement given the user's plates.
+    ///
+    pub fn drop_sets_by(
+        &self,
+        requirement: &Requirement,
+        percentage: f64,
+    ) -> Result<Vec<Dumbbell>, GymError> {
+        if !Self::valid_percentage(percentage) {
+            return Err(GymError::InvalidPercentage(percentage));
         }
 
-        for ((i1, state1), (i2, state2)) in states.iter().enumerate().tuple_combinations() {
-            if state1.adjacent(state2) {
-                graph.add_edge(GymStateId(i1), GymStateId(i2), 1);
+        let requirement = self.rounded(requirement.clone());
+
+        self.loadings(&requirement)
+            .map(|top| {
+                let prefixes = top.prefixes();
+                let mut sets = vec![top.clone()];
+                let mut current = prefixes.len() - 1;
+
+                while current > 0 {
+                    let weight = f64::from(prefixes[current].weight());
+                    let drop = |i: usize| (1.0 - f64::from(prefixes[i].weight()) / weight) * 100.0;
+                    let Some(next) = (0..current)
+                        .min_by(|i, j| {
+                            (drop(*i) - percentage)
+                                .abs()
+                                .total_cmp(&(drop(*j) - percentage).abs())
+                        })
+                        .filter(|next| drop(*next) <= percentage * 2.0)
+                    else {
+                        break;
+                    };
+
+                    sets.push(prefixes[next].clone());
+                    current = next;
+                }
+
+                let error = Self::drop_percentages(&sets)
+                    .iter()
+                    .map(|drop| (drop - percentage).powi(2))
+                    .sum::<f64>();
+
+                (sets, error)
+            })
+            .min_by(|(sets1, error1), (sets2, error2)| {
+                sets2.len().cmp(&sets1.len()).then(error1.total_cmp(error2))
+            })
+            .map(|(sets, _)| sets)
+            .ok_or(GymError::InvalidRequirement(requirement))
+    }
+
+    /// Every loading of the requirement's bar kind that meets it.
+    fn loadings<'a>(&'a self, requirement: &'a Requirement) -> impl Iterator<Item = &'a Dumbbell> {
+        self.bar_options
+            .get(&requirement.bar_kind())
+            .into_iter()
+            .flatten()
+            .flat_map(|bar| self.graphs[bar].dumbbells())
+            .filter(|dumbbell| requirement.matches(dumbbell))
+    }
+
+    /// Whether `percentage` is strictly between 0 and 100, which leaves out NaN.
+    fn valid_percentage(percentage: f64) -> bool {
+        percentage > 0.0 && percentage < 100.0
+    }
+
+    /// How much of the weight each drop between consecutive sets takes off, as a percentage.
+    fn drop_percentages(sets: &[Dumbbell]) -> Vec<f64> {
+        sets.iter()
+            .tuple_windows()
+            .map(|(from, to)| (1.0 - f64::from(to.weight()) / f64::from(from.weight())) * 100.0)
+            .collect()
+    }
+
+    /// Settles which weights meet the requirement, out of those that can be built on any bar of
+    /// its kind.
+    fn rounded(&self, requirement: Requirement) -> Requirement {
+        let achievable = self
+            .bar_options
+            .get(&requirement.bar_kind())
+            .into_iter()
+            .flatten()
+            .flat_map(|bar| self.graphs[bar].dumbbells())
+            .filter(|dumbbell| dumbbell.handles() >= requirement.handles())
+            .map(Dumbbell::weight)
+            .sorted()
+            .dedup()
+            .collect_vec();
+
+        requirement.rounded(self.rounding, &achievable)
+    }
+
+    /// Turns the loadings each superset was met with into the steps of a plan.
+    fn plan(
+        &self,
+        start: Option<&GymState>,
+        bars: &[Bar],
+        sequence: Vec<Met>,
+        strip: bool,
+        cost: u32,
+    ) -> Plan {
+        let start = start.cloned().unwrap_or_default();
+        let mut state = start.clone();
+        let mut steps = Vec::with_capacity(sequence.len());
+
+        for (superset, assigned, loadings) in sequence {
+            let previous = state.clone();
+            for (bar, loading) in bars.iter().zip(loadings) {
+                if let Some(loading) = loading {
+                    state.insert(bar.clone(), self.graphs[bar].dumbbell(loading).clone());
+                }
             }
+
+            steps.push(Step::new(superset, assigned, &previous, state.clone()));
         }
 
-        graph
+        let end = if strip { state.stripped() } else { state };
+
+        Plan::new(&start, steps, end, cost)
     }
 
-    fn find_states_for_requirement(&self, requirement: Requirement) -> Vec<GymStateId> {
-        let matching_states: Vec<(GymStateId, usize)> = self.states[&requirement.bar_kind()]
-            .iter()
-            .enumerate()
-            .filter_map(|(i, state)| {
-                let bars = self.bar_options.get(&requirement.bar_kind())?;
-
-                for bar in bars {
-                    if let Some(dumbbell) = state.get(bar) {
-                        if dumbbell.weight() == requirement.weight() {
-                            return Some((GymStateId(i), dumbbell.plates().len()));
-                        }
+    fn usage(&self, bars: &[Bar], loadings: &Loadings) -> HashMap<Plate, usize> {
+        bars.iter()
+            .zip(loadings)
+            .fold(HashMap::new(), |mut acc, (bar, loading)| {
+                if let Some(loading) = loading {
+                    for (plate, count) in self.graphs[bar].usage(*loading) {
+                        *acc.entry(*plate).or_default() += count;
                     }
                 }
-                None
+                acc
             })
-            .collect();
+    }
 
-        matching_states
+    fn fits(plates: &HashMap<Plate, usize>, usage: &HashMap<Plate, usize>) -> bool {
+        usage.iter().all(|(plate, count)| {
+            plates
+                .get(plate)
+                .is_some_and(|available| count <= available)
+        })
+    }
+
+    /// The plates there aren't enough of to swap `removed` for `added` on one bar on top of
+    /// `usage`.
+    fn shortfall(
+        &self,
+        usage: &HashMap<Plate, usize>,
+        removed: Option<&HashMap<Plate, usize>>,
+        added: &HashMap<Plate, usize>,
+    ) -> Vec<Plate> {
+        added
+            .iter()
+            .filter(|(plate, count)| {
+                let in_use = usage.get(plate).copied().unwrap_or_default()
+                    - removed
+                        .and_then(|r| r.get(plate))
+                        .copied()
+                        .unwrap_or_default();
+
+                self.plates
+                    .get(plate)
+                    .is_none_or(|available| in_use + **count > *available)
+            })
+            .map(|(plate, _)| *plate)
+            .collect()
+    }
+
+    fn dumbbells(weights_map: &HashMap<Plate, usize>, bar: &Bar) -> Vec<Dumbbell> {
+        (1..=bar.kind().handles())
+            .flat_map(|handles| {
+                let required = bar.kind().required_similar_plates() * handles;
+
+                Self::available_dumbbells(
+                    &weights_map
+                        .iter()
+                        .filter(|(plate, count)| {
+                            bar.kind().loadable()
+                                && plate.gauge() == bar.gauge()
+                                && **count >= required
+                        })
+                        .map(|(plate, count)| (*plate, count / required))
+                        .collect::<Vec<_>>(),
+                    bar,
+                    handles,
+                )
+            })
+            .collect::<HashSet<_>>()
             .into_iter()
-            .sorted_by_key(|(_, complexity): &(GymStateId, usize)| *complexity)
-            .map(|(id, _)| id)
+            .sorted_by(|d1, d2| {
+                d1.cmp(d2)
+                    .then_with(|| d1.plates().len().cmp(&d2.plates().len()))
+                    .then_with(|| d2.handles().cmp(&d1.handles()))
+                    .then_with(|| d1.plates().cmp(d2.plates()))
+            })
             .collect()
     }
 
-    fn find_optimal_sequence(
+    fn available_dumbbells(
+        plates: &[(Plate, usize)],
+        bar: &Bar,
+        handles: usize,
+    ) -> HashSet<Dumbbell> {
+        plates
+            .iter()
+            .map(|(plate, count)| (0..=*count).map(|n| vec![*plate; n]))
+            .multi_cartesian_product()
+            .map(|plates| Dumbbell::with_handles(plates.concat(), bar.clone(), handles))
+            .collect::<HashSet<_>>()
+    }
+
+    /// Marks every superset of the current block that the loadings already satisfy as met,
+    /// moving on to the next block once they all are.
+    fn advance(&self, bars: &[Bar], blocks: &[Vec<Superset>], mut node: SearchNode) -> SearchNode {
+        while node.progress < blocks.len() {
+            for (done, superset) in node.done.iter_mut().zip(&blocks[node.progress]) {
+                *done = *done || self.assignment(bars, superset, &node.loadings).is_some();
+            }
+
+            if !node.done.iter().all(|done| *done) {
+                break;
+            }
+
+            node.progress += 1;
+            node.done = vec![false; blocks.get(node.progress).map_or(0, Vec::len)];
+        }
+
+        node
+    }
+
+    /// The bar that meets each requirement of the superset with the given loadings, or `None`
+    /// if they can't all be met at once.
+    fn assignment(
         &self,
-        bar_kind: BarKind,
-        requirements: &[Requirement],
-    ) -> Result<Vec<GymStateId>, GymError> {
-        let requirement_states: Vec<Vec<GymStateId>> = requirements
+        bars: &[Bar],
+        superset: &Superset,
+        loadings: &Loadings,
+    ) -> Option<Vec<Bar>> {
+        let loaded = bars
             .iter()
-            .map(|req| self.find_states_for_requirement(*req))
-            .collect();
+            .zip(loadings)
+            .filter_map(|(bar, loading)| Some(self.graphs[bar].dumbbell((*loading)?)))
+            .collect_vec();
 
-        let n = requirement_states.len();
+        superset.assign(&loaded).map(|dumbbells| {
+            dumbbells
+                .iter()
+                .map(|dumbbell| dumbbell.bar().clone())
+                .collect()
+        })
+    }
+
+    /// Every node reachable from `node` with a single move, along with what it costs. Bars of the
+    /// kinds the current block still needs can make any move, or be set up for one of its
+    /// requirements if they haven't been used yet. Other bars only have plates taken off once
+    /// the plates are needed elsewhere, since any other change to them costs the same when it
+    /// is put off until they are needed.
+    fn neighbours(
+        &self,
+        bars: &[Bar],
+        blocks: &[Vec<Superset>],
+        node: &SearchNode,
+    ) -> Vec<(SearchNode, u32)> {
+        let usage = self.usage(bars, &node.loadings);
+        let requirements = blocks
+            .get(node.progress)
+            .into_iter()
+            .flatten()
+            .zip(&node.done)
+            .filter(|(_, done)| !**done)
+            .flat_map(|(superset, _)| superset.requirements())
+            .collect_vec();
 
-        match n {
-            0 => return Ok(vec![]),
-            1 => {
-                return requirement_states[0]
+        let mut neighbours = Vec::new();
+        let mut short = HashSet::<Plate>::new();
+        let mut others = Vec::new();
+
+        for (i, (bar, loading)) in bars.iter().zip(&node.loadings).enumerate() {
+            let graph = &self.graphs[bar];
+
+            if !requirements
+                .iter()
+                .any(|requirement| requirement.bar_kind() == *bar.kind())
+            {
+                others.push(i);
+                continue;
+            }
+
+            let moves: Vec<(usize, u32)> = match loading {
+                Some(loading) => graph.edges(*loading).to_vec(),
+                None => (0..graph.dumbbells().len())
+                    .filter(|next| {
+                        requirements
+                            .iter()
+                            .any(|requirement| requirement.matches(graph.dumbbell(*next)))
+                    })
+                    .map(|next| (next, 0))
+                    .collect(),
+            };
+
+            for (next, cost) in moves {
+                let missing = self.shortfall(
+                    &usage,
+                    loading.map(|loading| graph.usage(loading)),
+                    graph.usage(next),
+                );
+
+                if missing.is_empty() {
+                    neighbours.push((self.moved(bars, blocks, node, i, next), cost));
+                } else {
+                    short.extend(missing);
+                }
+            }
+        }
+
+        for i in others {
+            let (bar, Some(loading)) = (&bars[i], node.loadings[i]) else {
+                continue;
+            };
+            let graph = &self.graphs[bar];
+            let current = graph.usage(loading);
+
+            for &(next, cost) in graph.edges(loading) {
+                let added = graph.usage(next);
+                let removal = added
                     .iter()
-                    .min_by_key(|id| self.states[&bar_kind][id.0].clone())
-                    .ok_or(GymError::InvalidRequirement(requirements[0]))
-                    .map(|id| vec![*id]);
+                    .all(|(plate, count)| current.get(plate).is_some_and(|c| count <= c));
+                let frees = current.iter().any(|(plate, count)| {
+                    short.contains(plate) && added.get(plate).copied().unwrap_or_default() < *count
+                });
+
+                if removal && frees {
+                    neighbours.push((self.moved(bars, blocks, node, i, next), cost));
+                }
             }
-            _ => {}
         }
 
-        let distances: HashMap<(GymStateId, GymStateId), u32> =
-            algo::johnson(&self.graphs[&bar_kind], |e| *e.2)
-                .map_err(|_| GymError::InvalidRequirement(requirements[0]))?
-                .into_iter()
-                .collect();
+        neighbours
+    }
+
+    /// `node` with bar `i` changed to loading `next`.
+    fn moved(
+        &self,
+        bars: &[Bar],
+        blocks: &[Vec<Superset>],
+        node: &SearchNode,
+        i: usize,
+        next: usize,
+    ) -> SearchNode {
+        let mut loadings = node.loadings.clone();
+        loadings[i] = Some(next);
+
+        let neighbour = SearchNode {
+            loadings,
+            progress: node.progress,
+            done: node.done.clone(),
+        };
+
+        self.advance(bars, blocks, neighbour)
+    }
+
+    fn plates_loaded(&self, bars: &[Bar], loadings: &Loadings) -> usize {
+        bars.iter()
+            .zip(loadings)
+            .filter_map(|(bar, loading)| {
+                Some(self.graphs[bar].dumbbell((*loading)?).plates().len())
+            })
+            .sum()
+    }
+
+    /// Whether every bar is bare or unused.
+    fn stripped(&self, bars: &[Bar], loadings: &Loadings) -> bool {
+        bars.iter().zip(loadings).all(|(bar, loading)| {
+            loading.is_none_or(|loading| self.graphs[bar].dumbbell(loading).plates().is_empty())
+        })
+    }
 
-        let mut dp: Vec<HashMap<GymStateId, (u32, Option<GymStateId>)>> = vec![HashMap::new(); n];
+    /// `node` with every bar stripped, along with what it costs.
+    fn strip(&self, bars: &[Bar], node: &SearchNode) -> (SearchNode, u32) {
+        let mut stripped = node.clone();
+        let mut cost = 0;
 
-        for &state in &requirement_states[0] {
-            dp[0].insert(state, (0, None));
+        for (bar, loading) in bars.iter().zip(&mut stripped.loadings) {
+            let graph = &self.graphs[bar];
+            if let Some(current) = *loading {
+                cost += graph.strip_cost(current);
+                *loading = graph.bare().or(*loading);
+            }
         }
 
-        for i in 1..n {
-            for &current_state in &requirement_states[i] {
-                let mut min_cost = u32::MAX;
-                let mut best_prev = None;
+        (stripped, cost)
+    }
 
-                let mut prev_states: Vec<_> = dp[i - 1]
+    /// The loading of every bar in `start`, or `None` for all of them when the starting state
+    /// isn't known.
+    fn start_loadings(&self, bars: &[Bar], start: Option<&GymState>) -> Result<Loadings, GymError> {
+        let Some(start) = start else {
+            return Ok(vec![None; bars.len()]);
+        };
+
+        bars.iter()
+            .map(|bar| {
+                let dumbbell = start
+                    .get(bar)
+                    .cloned()
+                    .unwrap_or_else(|| Dumbbell::new(vec![], bar.clone()));
+
+                self.graphs[bar]
+                    .dumbbells()
                     .iter()
-                    .map(|(&state, &(cost, _))| (state, cost))
-                    .collect();
-                prev_states.sort_by_key(|&(state, _)| state);
-
-                for (prev_state, prev_cost) in prev_states {
-                    if let Some(&transition_cost) = distances.get(&(prev_state, current_state)) {
-                        let total_cost = prev_cost.saturating_add(transition_cost);
-
-                        if total_cost < min_cost {
-                            min_cost = total_cost;
-                            best_prev = Some(prev_state);
-                        }
-                    }
-                }
+                    .position(|candidate| *candidate == dumbbell)
+                    .map(Some)
+                    .ok_or_else(|| GymError::InvalidStart(start.clone()))
+            })
+            .collect()
+    }
 
-                if min_cost != u32::MAX {
-                    dp[i].insert(current_state, (min_cost, best_prev));
+    /// Searches for the cheapest way through the blocks in order, returning the supersets in
+    /// the order they are met along with the loadings in place for each, and the total cost,
+    /// including stripping the bars afterwards if `strip` is set. Nodes are searched in order
+    /// of their cost plus an estimate of what is left that never overshoots, so the first
+    /// goal reached is the cheapest.
+    fn find_optimal_sequence(
+        &self,
+        bars: &[Bar],
+        blocks: &[Vec<Superset>],
+        start: Option<&GymState>,
+        strip: bool,
+    ) -> Result<(Vec<Met>, u32), GymError> {
+        for requirement in blocks.iter().flatten().flat_map(Superset::requirements) {
+            let possible = bars.iter().any(|bar| {
+                self.graphs[bar]
+                    .dumbbells()
+                    .iter()
+                    .any(|dumbbell| requirement.matches(dumbbell))
+            });
+
+            if !possible {
+                return Err(GymError::InvalidRequirement(requirement.clone()));
+            }
+        }
+
+        let start = self.advance(
+            bars,
+            blocks,
+            SearchNode {
+                loadings: self.start_loadings(bars, start)?,
+                progress: 0,
+                done: vec![false; blocks.first().map_or(0, Vec::len)],
+            },
+        );
+
+        let estimate = Estimate::new(
+            &self.graphs,
+            &mut self.distances.borrow_mut(),
+            bars,
+            blocks,
+            strip,
+        );
+        let remaining = estimate.remaining(start.progress, &start.done, &start.loadings);
+
+        let mut costs =
+            HashMap::<SearchNode, (u32, Option<SearchNode>)>::from([(start.clone(), (0, None))]);
+        let mut furthest = (start.progress, start.done.clone());
+        let mut frontier = BinaryHeap::from([Reverse((remaining, Reverse(0_u32), 0_usize, start))]);
+
+        while let Some(Reverse((_, Reverse(cost), _, node))) = frontier.pop() {
+            if costs.get(&node).is_some_and(|(best, _)| cost > *best) {
+                continue;
+            }
+
+            if node.progress == blocks.len() && (!strip || self.stripped(bars, &node.loadings)) {
+                return Ok((self.sequence(bars, blocks, &costs, node), cost));
+            }
+
+            let neighbours = if node.progress == blocks.len() {
+                vec![self.strip(bars, &node)]
+            } else {
+                self.neighbours(bars, blocks, &node)
+            };
+
+            let met = |done: &[bool]| done.iter().filter(|done| **done).count();
+            if (node.progress, met(&node.done)) > (furthest.0, met(&furthest.1)) {
+                furthest = (node.progress, node.done.clone());
+            }
+
+            for (neighbour, step) in neighbours {
+                let total = cost.saturating_add(step);
+                if costs
+                    .get(&neighbour)
+                    .is_none_or(|(current, _)| total < *current)
+                {
+                    costs.insert(neighbour.clone(), (total, Some(node.clone())));
+                    let remaining = estimate.remaining(
+                        neighbour.progress,
+                        &neighbour.done,
+                        &neighbour.loadings,
+                    );
+                    let plates = self.plates_loaded(bars, &neighbour.loadings);
+                    frontier.push(Reverse((
+                        total.saturating_add(remaining),
+                        Reverse(total),
+                        plates,
+                        neighbour,
+                    )));
                 }
             }
         }
 
-        let (&final_state, _) = dp[n - 1]
-            .iter()
-            .min_by_key(|(_, (cost, _))| *cost)
-            .ok_or(GymError::InvalidRequirement(requirements[n - 1]))?;
+        let (progress, done) = furthest;
+        let Some(superset) = blocks.get(progress).and_then(|supersets| {
+            supersets
+                .iter()
+                .zip(&done)
+                .find(|(_, done)| !**done)
+                .map(|(superset, _)| superset.clone())
+        }) else {
+            return Err(GymError::InvalidStrip);
+        };
+
+        Err(match superset.requirements() {
+            [requirement] => GymError::InvalidRequirement(requirement.clone()),
+            _ => GymError::InvalidSuperset(superset),
+        })
+    }
+
+    /// Walks back from the goal and replays the path, recording each superset along with the
+    /// bars and loadings it was met with.
+    fn sequence(
+        &self,
+        bars: &[Bar],
+        blocks: &[Vec<Superset>],
+        costs: &HashMap<SearchNode, (u32, Option<SearchNode>)>,
+        goal: SearchNode,
+    ) -> Vec<Met> {
+        let mut path = vec![goal];
+        while let Some((_, Some(previous))) = costs.get(&path[path.len() - 1]) {
+            path.push(previous.clone());
+        }
 
-        let mut path = Vec::new();
-        let mut current = final_state;
-        path.push(current);
+        let mut sequence = Vec::new();
+        let mut progress = 0;
+        let mut done = vec![false; blocks.first().map_or(0, Vec::len)];
 
-        for i in (0..n - 1).rev() {
-            if let Some((_, Some(prev))) = dp[i + 1].get(&current) {
-                current = *prev;
-                path.push(current);
+        for node in path.iter().rev() {
+            for (block, supersets) in blocks.iter().enumerate().skip(progress) {
+                if block > node.progress {
+                    break;
+                }
+
+                for (i, superset) in supersets.iter().enumerate() {
+                    let was_done = block == progress && done[i];
+                    let is_done = block < node.progress || node.done[i];
+
+                    if is_done && !was_done {
+                        let assigned = self
+                            .assignment(bars, superset, &node.loadings)
+                            .unwrap_or_default();
+                        sequence.push((superset.clone(), assigned, node.loadings.clone()));
+                    }
+                }
             }
+
+            progress = node.progress;
+            done.clone_from(&node.done);
         }
 
-        path.reverse();
-        Ok(path)
+        sequence
     }
 }
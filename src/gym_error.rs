@@ -1,9 +1,54 @@
-use thiserror::Error;
+use std::fmt::Display;
 
-use crate::requirement::Requirement;
+use crate::{
+    gym_state::GymState,
+    requirement::Requirement,
+    superset::Superset,
+    unit::{Unit, UnitDisplay},
+};
 
-#[derive(Error, Debug)]
+/// Displayed in kg like the rest of the crate, or in any unit through [`UnitDisplay`], since
+/// most of its variants show weights.
+#[derive(Debug)]
 pub enum GymError {
-    #[error("Cannot construct {0} with available plates and bars.")]
     InvalidRequirement(Requirement),
+    InvalidSuperset(Superset),
+    InvalidStart(GymState),
+    InvalidStrip,
+    InvalidPercentage(f64),
 }
+
+impl UnitDisplay for GymError {
+    fn fmt_in(&self, f: &mut std::fmt::Formatter<'_>, unit: Unit) -> std::fmt::Result {
+        match self {
+            GymError::InvalidRequirement(requirement) => write!(
+                f,
+                "Cannot construct {} with available plates and bars.",
+                requirement.in_unit(unit)
+            ),
+            GymError::InvalidSuperset(superset) => write!(
+                f,
+                "Cannot load {} at the same time with available plates and bars.",
+                superset.in_unit(unit)
+            ),
+            GymError::InvalidStart(state) => write!(
+                f,
+                "Cannot start from the following with available plates:\n{}",
+                state.in_unit(unit)
+            ),
+            GymError::InvalidStrip => write!(f, "Cannot strip every bar once the session is over."),
+            GymError::InvalidPercentage(percentage) => write!(
+                f,
+                "Invalid percentage {percentage}, expected one between 0 and 100."
+            ),
+        }
+    }
+}
+
+impl Display for GymError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.fmt_in(f, Unit::Kg)
+    }
+}
+
+impl std::error::Error for GymError {}
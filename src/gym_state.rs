@@ -1,11 +1,15 @@
 use std::{collections::HashMap, fmt::Display};
 
-use crate::{bar::Bar, dumbbell::Dumbbell};
+use itertools::Itertools;
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct GymStateId(pub usize);
+use crate::{
+    bar::Bar,
+    dumbbell::Dumbbell,
+    plate::Plate,
+    unit::{Unit, UnitDisplay},
+};
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct GymState {
     state: HashMap<Bar, Dumbbell>,
 }
@@ -17,62 +21,55 @@ impl GymState {
     }
 
     #[must_use]
-    pub fn adjacent(&self, other: &Self) -> bool {
-        let mut adjacencies = 0;
-
-        for (bar, dumbbell) in &self.state {
-            if let Some(other_dumbbell) = other.get(bar) {
-                let same = dumbbell == other_dumbbell;
-                let adjacent = dumbbell.adjacent(other_dumbbell);
-
-                if !(same || adjacent) {
-                    return false;
-                }
-
-                if adjacent {
-                    adjacencies += 1;
-                }
-
-                if adjacencies > 1 {
-                    return false;
-                }
-            }
-        }
+    pub fn get(&self, bar: &Bar) -> Option<&Dumbbell> {
+        self.state.get(bar)
+    }
 
-        adjacencies == 1
+    /// Replaces whatever is loaded on `bar`.
+    pub fn insert(&mut self, bar: Bar, dumbbell: Dumbbell) {
+        self.state.insert(bar, dumbbell);
     }
 
+    /// The same bars with every plate taken off.
     #[must_use]
-    pub fn get(&self, bar: &Bar) -> Option<&Dumbbell> {
-        self.state.get(bar)
+    pub fn stripped(&self) -> Self {
+        GymState::new(
+            self.state
+                .keys()
+                .map(|bar| (bar.clone(), Dumbbell::new(vec![], bar.clone())))
+                .collect(),
+        )
+    }
+
+    /// Every loaded bar, in a stable order.
+    pub fn iter(&self) -> impl Iterator<Item = (&Bar, &Dumbbell)> {
+        self.state.iter().sorted_by_key(|(bar, _)| *bar)
     }
 
     #[must_use]
-    pub fn plates(&self) -> usize {
+    pub fn plate_usage(&self) -> HashMap<Plate, usize> {
         self.state
             .values()
-            .map(|dumbbell| dumbbell.plates().len())
-            .sum()
+            .fold(HashMap::new(), |mut acc, dumbbell| {
+                for (plate, count) in dumbbell.plate_usage() {
+                    *acc.entry(plate).or_default() += count;
+                }
+                acc
+            })
     }
 }
 
-impl Display for GymState {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for (bar, dumbbell) in &self.state {
-            writeln!(f, "{bar}: {dumbbell}")?;
+impl UnitDisplay for GymState {
+    fn fmt_in(&self, f: &mut std::fmt::Formatter<'_>, unit: Unit) -> std::fmt::Result {
+        for (bar, dumbbell) in self.iter() {
+            writeln!(f, "{}: {}", bar.in_unit(unit), dumbbell.in_unit(unit))?;
         }
         Ok(())
     }
 }
 
-impl Ord for GymState {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.plates().cmp(&other.plates())
-    }
-}
-
-impl PartialOrd for GymState {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
+impl Display for GymState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.fmt_in(f, Unit::Kg)
     }
 }
@@ -0,0 +1,149 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use serde::{Deserialize, de::DeserializeOwned};
+
+use crate::{
+    bar::Bar,
+    bar_kind::BarKind,
+    collar::Collar,
+    inventory_error::InventoryError,
+    plate::Plate,
+    unit::{Unit, entry_grams},
+};
+
+#[derive(Debug, Deserialize)]
+struct PlateEntry {
+    weight: f64,
+    #[serde(default)]
+    unit: Option<Unit>,
+    gauge: u32,
+    quantity: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct CollarEntry {
+    weight: f64,
+    #[serde(default)]
+    unit: Option<Unit>,
+    gauge: u32,
+    quantity: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct BarEntry {
+    weight: f64,
+    #[serde(default)]
+    unit: Option<Unit>,
+    #[serde(default)]
+    gauge: Option<u32>,
+    #[serde(default)]
+    collars: bool,
+    #[serde(default)]
+    name: Option<String>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Inventory {
+    plates: HashMap<Plate, usize>,
+    bars: Vec<Bar>,
+}
+
+impl Inventory {
+    /// Loads the plate and collar inventories along with one bar inventory file per bar kind.
+    /// Bars marked as needing collars are each given a set of matching gauge from the collar
+    /// inventory, in the order they are listed. Identical bars are numbered in the order they
+    /// are listed so that each of them is kept. Weights are in grams unless an entry gives its
+    /// `unit`, e.g. `{ "weight": 45, "unit": "lb" }`, so kg and lb plates can be mixed. Only
+    /// fixed-weight implements can leave out their `gauge`.
+    ///
+    /// # Errors
+    /// If any of the inventory files cannot be read or parsed, if a bar that takes plates has
+    /// no gauge, or if there aren't enough collars for the bars that need them.
+    ///
+    pub fn load(
+        plates: &Path,
+        collars: &Path,
+        bars: &[(BarKind, &Path)],
+    ) -> Result<Self, InventoryError> {
+        let plates = Self::read::<Vec<PlateEntry>>(plates)?.into_iter().fold(
+            HashMap::new(),
+            |mut acc, entry| {
+                *acc.entry(Plate::new(
+                    entry_grams(entry.weight, entry.unit),
+                    entry.gauge,
+                ))
+                .or_default() += entry.quantity;
+                acc
+            },
+        );
+
+        let mut collars = Self::read::<Vec<CollarEntry>>(collars)?
+            .into_iter()
+            .map(|entry| {
+                (
+                    Collar::new(entry_grams(entry.weight, entry.unit), entry.gauge),
+                    entry.quantity,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let mut loaded_bars = Vec::new();
+
+        for &(kind, path) in bars {
+            for (i, entry) in Self::read::<Vec<BarEntry>>(path)?.into_iter().enumerate() {
+                let gauge = match entry.gauge {
+                    Some(gauge) => gauge,
+                    None if !kind.loadable() => 0,
+                    None => return Err(InventoryError::MissingGauge(path.to_path_buf(), i + 1)),
+                };
+                let mut bar = Bar::new(entry_grams(entry.weight, entry.unit), gauge, kind);
+
+                if entry.collars {
+                    let needed = kind.required_similar_plates() * kind.handles();
+                    let (collar, remaining) = collars
+                        .iter_mut()
+                        .find(|(collar, remaining)| {
+                            collar.gauge() == bar.gauge() && *remaining >= needed
+                        })
+                        .ok_or(InventoryError::MissingCollars(bar.clone()))?;
+
+                    *remaining -= needed;
+                    bar = bar.with_collar(*collar);
+                }
+
+                let id = loaded_bars
+                    .iter()
+                    .filter(|loaded: &&Bar| loaded.identical(&bar))
+                    .count();
+                bar = bar.with_id(id);
+
+                if let Some(name) = &entry.name {
+                    bar = bar.with_name(name);
+                }
+
+                loaded_bars.push(bar);
+            }
+        }
+
+        Ok(Inventory {
+            plates,
+            bars: loaded_bars,
+        })
+    }
+
+    #[must_use]
+    pub fn plates(&self) -> &HashMap<Plate, usize> {
+        &self.plates
+    }
+
+    #[must_use]
+    pub fn bars(&self) -> &[Bar] {
+        &self.bars
+    }
+
+    fn read<T: DeserializeOwned>(path: &Path) -> Result<T, InventoryError> {
+        let contents =
+            fs::read_to_string(path).map_err(|e| InventoryError::Io(path.to_path_buf(), e))?;
+        serde_json::from_str(&contents).map_err(|e| InventoryError::Parse(path.to_path_buf(), e))
+    }
+}
@@ -0,0 +1,17 @@
+use std::path::PathBuf;
+
+use thiserror::Error;
+
+use crate::bar::Bar;
+
+#[derive(Error, Debug)]
+pub enum InventoryError {
+    #[error("Cannot read inventory file {0}: {1}")]
+    Io(PathBuf, std::io::Error),
+    #[error("Cannot parse inventory file {0}: {1}")]
+    Parse(PathBuf, serde_json::Error),
+    #[error("Bar {1} in inventory file {0} takes plates but has no gauge.")]
+    MissingGauge(PathBuf, usize),
+    #[error("Not enough collars for {0}.")]
+    MissingCollars(Bar),
+}
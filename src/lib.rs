@@ -1,11 +1,29 @@
 #![warn(clippy::pedantic)]
 
+pub mod args;
 pub mod bar;
 pub mod bar_kind;
+pub mod bar_loading;
+pub mod block;
+pub mod collar;
+pub mod cost_model;
 pub mod dumbbell;
+pub mod estimate;
 pub mod graph;
 pub mod gym;
 pub mod gym_error;
 pub mod gym_state;
+pub mod inventory;
+pub mod inventory_error;
+pub mod plan;
 pub mod plate;
+pub mod profile;
+pub mod profile_error;
 pub mod requirement;
+pub mod rounding;
+pub mod saved_state;
+pub mod saved_state_error;
+pub mod session;
+pub mod session_error;
+pub mod superset;
+pub mod unit;
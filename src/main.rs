@@ -1,80 +1,186 @@
-use std::collections::HashMap;
+use std::path::Path;
 
 use anyhow::Ok;
 use clap::Parser;
 use itertools::Itertools;
-use workout_rs::{bar::Bar, bar_kind::BarKind, gym::Gym, plate::Plate, requirement::Requirement};
-
-#[derive(Parser)]
-struct Args {
-    #[arg(value_parser = clap::value_parser!(Requirement))]
-    requirements: Vec<Requirement>,
-}
+use workout_rs::{
+    args::{Args, Command, StateCommand},
+    bar::Bar,
+    bar_kind::BarKind,
+    dumbbell::Dumbbell,
+    gym::Gym,
+    gym_error::GymError,
+    inventory::Inventory,
+    plan::Change,
+    profile::Profile,
+    saved_state::SavedState,
+    session::Session,
+    unit::{Unit, UnitDisplay},
+};
 
 fn main() -> anyhow::Result<()> {
-    let args = Args::parse();
-
-    let small_plates = Plate::from_weights_map(HashMap::from([(500, 4), (1250, 4), (2500, 4)]), 1);
-
-    let big_plates = Plate::from_weights_map(
-        HashMap::from([
-            (1250, 8),
-            (2500, 12),
-            (5000, 2),
-            (10000, 2),
-            (15000, 2),
-            (20000, 2),
-        ]),
-        2,
-    );
-
-    let plates = small_plates
-        .into_iter()
-        .chain(big_plates)
-        .collect::<HashMap<_, _>>();
-
-    let bars = vec![
-        Bar::new(2000, 1, BarKind::Dumbbell),
-        Bar::new(5000, 2, BarKind::Dumbbell),
-        Bar::new(15000, 2, BarKind::Barbell),
-    ];
-
-    process_bars(&plates, &bars, &args.requirements)?;
+    let args = Args::parse().checked().unwrap_or_else(|e| e.exit());
+
+    let inventory = Inventory::load(
+        &args.plates,
+        &args.collars,
+        &[
+            (BarKind::Dumbbell, &args.dumbbells),
+            (BarKind::Barbell, &args.barbells),
+            (BarKind::EzBar, &args.ez_bars),
+            (BarKind::TrapBar, &args.trap_bars),
+            (BarKind::Fixed, &args.fixed),
+        ],
+    )?;
+
+    if let Some(Command::State(command)) = &args.command {
+        return process_state(inventory.bars(), &args.state, command, args.unit);
+    }
+
+    let gym = Gym::new(
+        inventory.plates(),
+        inventory.bars(),
+        args.cost.model().as_ref(),
+    )
+    .with_rounding(args.rounding);
+    let profile = Profile::load(&args.profile)?.unwrap_or_default();
+
+    if let Some(Command::WarmUp {
+        requirement,
+        steps,
+        percentages,
+    }) = &args.command
+    {
+        let requirement = profile.resolve(requirement)?;
+        let ramp = match percentages.is_empty() {
+            true => gym.warm_up_steps(&requirement, usize::from(*steps)),
+            false => gym.warm_up(&requirement, percentages),
+        }
+        .map_err(|e| gym_error(&e, args.unit))?;
+        print_sets(&ramp, ramp.last(), args.unit);
+        return Ok(());
+    }
+
+    if let Some(Command::DropSet {
+        requirement,
+        drops,
+        percentage,
+    }) = &args.command
+    {
+        let requirement = profile.resolve(requirement)?;
+        let sets = match percentage {
+            Some(percentage) => gym.drop_sets_by(&requirement, *percentage),
+            None => gym.drop_sets(&requirement, *drops),
+        }
+        .map_err(|e| gym_error(&e, args.unit))?;
+        print_sets(&sets, sets.first(), args.unit);
+        return Ok(());
+    }
+
+    if args.requirements.is_empty()
+        && args.session.is_none()
+        && args.start.is_empty()
+        && !args.strip
+    {
+        print_weights(&gym, args.unit);
+        return Ok(());
+    }
+
+    let start = match args.start.is_empty() {
+        true => SavedState::load(&args.state)?,
+        false => Some(SavedState::new(args.start.clone())),
+    }
+    .map(|start| start.state(inventory.bars()))
+    .transpose()
+    .map_err(anyhow::Error::msg)?;
+
+    let session = match &args.session {
+        Some(path) => Session::read(path)?,
+        None => Session::new(args.requirements.clone()),
+    };
+
+    let blocks = session
+        .blocks()
+        .iter()
+        .map(|block| profile.resolve_block(block))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let plan = gym
+        .order_with(start.as_ref(), &blocks, args.strip)
+        .map_err(|e| gym_error(&e, args.unit))?;
+    print!("{}", plan.in_unit(args.unit));
+
+    if !args.no_save {
+        SavedState::from_state(plan.end(), inventory.bars()).save(&args.state)?;
+    }
 
     Ok(())
 }
 
-fn process_bars(
-    plates: &HashMap<Plate, usize>,
+/// Prints each set with its weight as a percentage of `reference`'s, and the plates to change
+/// from the set before.
+fn print_sets(sets: &[Dumbbell], reference: Option<&Dumbbell>, unit: Unit) {
+    let reference = reference.map_or(1, Dumbbell::weight);
+
+    for (i, dumbbell) in sets.iter().enumerate() {
+        let percentage = f64::from(dumbbell.weight()) / f64::from(reference) * 100.0;
+        println!(
+            "{}. {}: {}, {:.0}%",
+            i + 1,
+            dumbbell.bar().in_unit(unit),
+            dumbbell.in_unit(unit),
+            percentage
+        );
+
+        if let Some(change) = i
+            .checked_sub(1)
+            .and_then(|previous| Change::between(&sets[previous], dumbbell))
+        {
+            println!("   - {}", change.in_unit(unit));
+        }
+    }
+}
+
+/// Shows `error` with its weights in `unit`.
+fn gym_error(error: &GymError, unit: Unit) -> anyhow::Error {
+    anyhow::Error::msg(error.in_unit(unit).to_string())
+}
+
+fn print_weights(gym: &Gym, unit: Unit) {
+    println!("Available weights ({unit}):");
+    let values = |weights: &[u32]| weights.iter().map(|w| unit.value(*w)).collect::<Vec<_>>();
+
+    for (bar, weights) in gym.weights().iter().sorted() {
+        println!("{}: {:?}", bar.in_unit(unit), values(weights));
+
+        if let Some(single) = gym.single_weights().get(bar) {
+            println!("{}, single handle: {:?}", bar.in_unit(unit), values(single));
+        }
+    }
+}
+
+fn process_state(
     bars: &[Bar],
-    requirements: &[Requirement],
+    path: &Path,
+    command: &StateCommand,
+    unit: Unit,
 ) -> anyhow::Result<()> {
-    let gym = Gym::new(plates, bars);
-
-    match requirements.is_empty() {
-        true => {
-            let weights = gym.weights();
-
-            println!("Available weights:");
-            for (bar, weights) in weights.iter().sorted() {
-                println!(
-                    "{}: {:?}",
-                    bar,
-                    weights
-                        .iter()
-                        .map(|w| *w as f64 / 1000.0)
-                        .collect::<Vec<_>>()
-                );
-            }
-        }
-        false => {
-            let ordered_dumbbells = gym.order(requirements)?;
-            for (bar, dumbbells) in ordered_dumbbells {
-                println!("{bar}");
-                for dumbbell in dumbbells {
-                    println!("  - {dumbbell}");
+    match command {
+        StateCommand::Show => match SavedState::load(path)? {
+            Some(saved) => {
+                let state = saved.state(bars).map_err(anyhow::Error::msg)?;
+                match state.iter().next() {
+                    Some(_) => print!("{}", state.in_unit(unit)),
+                    None => println!("Every bar is bare."),
                 }
             }
+            None => println!("No state saved yet."),
+        },
+        StateCommand::Reset => SavedState::default().save(path)?,
+        StateCommand::Set { loadings } => {
+            let saved = SavedState::new(loadings.clone());
+            saved.state(bars).map_err(anyhow::Error::msg)?;
+            saved.save(path)?;
         }
     }
 
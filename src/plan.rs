@@ -0,0 +1,328 @@
+use std::fmt::Display;
+
+use itertools::Itertools;
+
+use crate::{
+    bar::Bar,
+    dumbbell::Dumbbell,
+    gym_state::GymState,
+    plate::Plate,
+    superset::Superset,
+    unit::{Unit, UnitDisplay, Weight},
+};
+
+/// The plates taken off and put on a single bar between two consecutive steps, per handle.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Change {
+    bar: Bar,
+    handles: Vec<(Vec<Plate>, Vec<Plate>)>,
+    collars: Vec<(bool, bool)>,
+}
+
+impl Change {
+    /// Returns `None` if nothing has to be done to get from `from` to `to`.
+    #[must_use]
+    pub fn between(from: &Dumbbell, to: &Dumbbell) -> Option<Self> {
+        let handles = from.handle_changes(to);
+
+        if handles
+            .iter()
+            .all(|(removed, added)| removed.is_empty() && added.is_empty())
+        {
+            return None;
+        }
+
+        let collars = (0..handles.len())
+            .map(|handle| match to.bar().collar() {
+                Some(_) => (
+                    !from.handle(handle).is_empty(),
+                    !to.handle(handle).is_empty(),
+                ),
+                None => (false, false),
+            })
+            .collect();
+
+        Some(Change {
+            bar: to.bar().clone(),
+            handles,
+            collars,
+        })
+    }
+
+    /// Every change made to go from `previous` to `state`, setting up bars missing from
+    /// `previous` from bare.
+    #[must_use]
+    pub fn between_states(previous: &GymState, state: &GymState) -> Vec<Self> {
+        state
+            .iter()
+            .filter_map(|(bar, dumbbell)| match previous.get(bar) {
+                Some(previous) => Change::between(previous, dumbbell),
+                None => Change::between(&Dumbbell::new(vec![], bar.clone()), dumbbell),
+            })
+            .collect()
+    }
+
+    #[must_use]
+    pub fn bar(&self) -> &Bar {
+        &self.bar
+    }
+
+    /// The plates taken off and put on each handle, outermost first for those taken off.
+    #[must_use]
+    pub fn handles(&self) -> &[(Vec<Plate>, Vec<Plate>)] {
+        &self.handles
+    }
+
+    /// Whether each handle has its collars on before and after the change, which is never the
+    /// case for bars without collars.
+    #[must_use]
+    pub fn collars(&self) -> &[(bool, bool)] {
+        &self.collars
+    }
+
+    /// The collars come off before any plate is moved and go back on once they all are.
+    fn describe(
+        (removed, added): &(Vec<Plate>, Vec<Plate>),
+        (before, after): (bool, bool),
+        unit: Unit,
+    ) -> String {
+        let weights = |plates: &[Plate]| {
+            plates
+                .iter()
+                .map(|p| Weight(p.weight()).in_unit(unit).to_string())
+                .join(", ")
+        };
+
+        if removed.is_empty() && added.is_empty() {
+            return "no change".to_string();
+        }
+
+        let mut steps = Vec::new();
+        if before {
+            steps.push("remove collar".to_string());
+        }
+        if !removed.is_empty() {
+            steps.push(format!("remove {}", weights(removed)));
+        }
+        if !added.is_empty() {
+            steps.push(format!("add {}", weights(added)));
+        }
+        if after {
+            let fit = if before { "refit" } else { "fit" };
+            steps.push(format!("{fit} collar"));
+        }
+
+        steps.join(", ")
+    }
+}
+
+impl UnitDisplay for Change {
+    fn fmt_in(&self, f: &mut std::fmt::Formatter<'_>, unit: Unit) -> std::fmt::Result {
+        write!(f, "{}: ", self.bar.in_unit(unit))?;
+
+        if self.handles.iter().zip(&self.collars).all_equal() {
+            write!(
+                f,
+                "{}",
+                Self::describe(&self.handles[0], self.collars[0], unit)
+            )?;
+            if self.handles.len() > 1 {
+                write!(f, " on each handle")?;
+            }
+            return Ok(());
+        }
+
+        let handles = self
+            .handles
+            .iter()
+            .zip(&self.collars)
+            .enumerate()
+            .map(|(i, (handle, collars))| {
+                format!(
+                    "handle {}: {}",
+                    i + 1,
+                    Self::describe(handle, *collars, unit)
+                )
+            })
+            .join("; ");
+
+        write!(f, "{handles}")
+    }
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.fmt_in(f, Unit::Kg)
+    }
+}
+
+/// A superset, the bar each of its requirements is met on, the state of the gym once it is met,
+/// and the changes made to get there from the step before.
+#[derive(Clone, Debug)]
+pub struct Step {
+    superset: Superset,
+    bars: Vec<Bar>,
+    state: GymState,
+    changes: Vec<Change>,
+}
+
+impl Step {
+    /// Bars missing from `previous` are set up from bare.
+    #[must_use]
+    pub fn new(superset: Superset, bars: Vec<Bar>, previous: &GymState, state: GymState) -> Self {
+        let changes = Change::between_states(previous, &state);
+
+        Step {
+            superset,
+            bars,
+            state,
+            changes,
+        }
+    }
+
+    #[must_use]
+    pub fn superset(&self) -> &Superset {
+        &self.superset
+    }
+
+    #[must_use]
+    pub fn state(&self) -> &GymState {
+        &self.state
+    }
+
+    #[must_use]
+    pub fn changes(&self) -> &[Change] {
+        &self.changes
+    }
+
+    /// The bar each requirement of the superset is met on, in order.
+    #[must_use]
+    pub fn bars(&self) -> &[Bar] {
+        &self.bars
+    }
+
+    /// The loaded bars that meet each requirement of the superset, in order.
+    #[must_use]
+    pub fn dumbbells(&self) -> Vec<&Dumbbell> {
+        self.bars
+            .iter()
+            .filter_map(|bar| self.state.get(bar))
+            .collect()
+    }
+}
+
+/// The steps of a session in the order they are performed, followed by whatever has to be done
+/// to leave the bars as required afterwards.
+#[derive(Clone, Debug, Default)]
+pub struct Plan {
+    steps: Vec<Step>,
+    finish: Vec<Change>,
+    end: GymState,
+    cost: u32,
+}
+
+impl Plan {
+    /// `cost` is the total cost of every move from `start` to `end`.
+    #[must_use]
+    pub fn new(start: &GymState, steps: Vec<Step>, end: GymState, cost: u32) -> Self {
+        let last = steps.last().map_or(start, Step::state);
+        let finish = Change::between_states(last, &end);
+
+        Plan {
+            steps,
+            finish,
+            end,
+            cost,
+        }
+    }
+
+    #[must_use]
+    pub fn steps(&self) -> &[Step] {
+        &self.steps
+    }
+
+    /// The changes made after the last step.
+    #[must_use]
+    pub fn finish(&self) -> &[Change] {
+        &self.finish
+    }
+
+    /// The state the bars are left in.
+    #[must_use]
+    pub fn end(&self) -> &GymState {
+        &self.end
+    }
+
+    #[must_use]
+    pub fn cost(&self) -> u32 {
+        self.cost
+    }
+}
+
+impl UnitDisplay for Plan {
+    fn fmt_in(&self, f: &mut std::fmt::Formatter<'_>, unit: Unit) -> std::fmt::Result {
+        for (i, step) in self.steps.iter().enumerate() {
+            write!(f, "{}.", i + 1)?;
+
+            for (j, requirement) in step.superset.requirements().iter().enumerate() {
+                let separator = if j == 0 { " " } else { " + " };
+                write!(f, "{separator}{}", requirement.in_unit(unit))?;
+
+                if requirement.sets() > 1 {
+                    write!(f, ", set {} of {}", step.superset.set(), requirement.sets())?;
+                }
+
+                if let Some(reps) = requirement.reps() {
+                    write!(f, ", {reps} reps")?;
+                }
+            }
+
+            writeln!(f)?;
+
+            for change in &step.changes {
+                writeln!(f, "   - {}", change.in_unit(unit))?;
+            }
+
+            for (requirement, dumbbell) in step.superset.requirements().iter().zip(step.dumbbells())
+            {
+                write!(
+                    f,
+                    "   = {}: {}",
+                    dumbbell.bar().in_unit(unit),
+                    dumbbell.in_unit(unit)
+                )?;
+
+                let offset = requirement.offset(dumbbell.weight());
+                if offset != 0 {
+                    let (lightest, heaviest) = requirement.range();
+                    let asked = Weight(dumbbell.weight().clamp(lightest, heaviest));
+                    let sign = if offset > 0 { '+' } else { '-' };
+                    let delta = Weight(u32::try_from(offset.unsigned_abs()).unwrap_or(u32::MAX));
+                    write!(
+                        f,
+                        " instead of {} ({sign}{})",
+                        asked.in_unit(unit),
+                        delta.in_unit(unit)
+                    )?;
+                }
+
+                writeln!(f)?;
+            }
+        }
+
+        if !self.finish.is_empty() {
+            writeln!(f, "Finally:")?;
+            for change in &self.finish {
+                writeln!(f, "   - {}", change.in_unit(unit))?;
+            }
+        }
+
+        writeln!(f, "Total cost: {}", self.cost)
+    }
+}
+
+impl Display for Plan {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.fmt_in(f, Unit::Kg)
+    }
+}
@@ -0,0 +1,108 @@
+use std::{collections::HashMap, fs, io::ErrorKind, path::Path};
+
+use serde::Deserialize;
+
+use crate::{
+    bar_kind::BarKind,
+    block::Block,
+    profile_error::ProfileError,
+    requirement::Requirement,
+    superset::Superset,
+    unit::{Unit, entry_grams},
+};
+
+#[derive(Debug, Deserialize)]
+struct LiftEntry {
+    lift: String,
+    kind: String,
+    max: f64,
+    #[serde(default)]
+    unit: Option<Unit>,
+}
+
+/// The one rep or training max of each lift on each bar kind it is done with, for
+/// requirements given as a percentage of it, e.g. `bench:80%b`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Profile {
+    maxes: HashMap<(String, BarKind), u32>,
+}
+
+impl Profile {
+    #[must_use]
+    pub fn new(maxes: HashMap<(String, BarKind), u32>) -> Self {
+        Profile { maxes }
+    }
+
+    /// Reads the lift profile at `path`, a list of lifts with the letter of the bar kind they
+    /// are done with and their max, e.g. `{ "lift": "bench", "kind": "b", "max": 100000 }`, in
+    /// grams unless the entry gives its `unit`. Returns `None` if there is no profile.
+    ///
+    /// # Errors
+    /// If the file exists but cannot be read or parsed, or if it names an unknown bar kind.
+    ///
+    pub fn load(path: &Path) -> Result<Option<Self>, ProfileError> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(ProfileError::Io(path.to_path_buf(), e)),
+        };
+
+        serde_json::from_str::<Vec<LiftEntry>>(&contents)
+            .map_err(|e| ProfileError::Parse(path.to_path_buf(), e))?
+            .into_iter()
+            .map(|entry| {
+                let kind = entry
+                    .kind
+                    .parse::<BarKind>()
+                    .map_err(|_| ProfileError::InvalidKind(entry.lift.clone(), entry.kind))?;
+
+                Ok(((entry.lift, kind), entry_grams(entry.max, entry.unit)))
+            })
+            .collect::<Result<_, _>>()
+            .map(|maxes| Some(Profile::new(maxes)))
+    }
+
+    #[must_use]
+    pub fn max(&self, lift: &str, bar_kind: BarKind) -> Option<u32> {
+        self.maxes.get(&(lift.to_string(), bar_kind)).copied()
+    }
+
+    /// Works out the weight of a requirement given as a percentage of its lift's max.
+    ///
+    /// # Errors
+    /// If there is no max for the lift on the requirement's bar kind.
+    ///
+    pub fn resolve(&self, requirement: &Requirement) -> Result<Requirement, ProfileError> {
+        if requirement.percent().is_none() {
+            return Ok(requirement.clone());
+        }
+
+        let lift = requirement.label().unwrap_or_default();
+
+        self.max(lift, requirement.bar_kind())
+            .map(|max| requirement.clone().of_max(max))
+            .ok_or_else(|| ProfileError::MissingMax(lift.to_string(), requirement.bar_kind()))
+    }
+
+    /// Works out the weight of every requirement in the block given as a percentage of its
+    /// lift's max.
+    ///
+    /// # Errors
+    /// If there is no max for one of the lifts on its bar kind.
+    ///
+    pub fn resolve_block(&self, block: &Block) -> Result<Block, ProfileError> {
+        block
+            .supersets()
+            .iter()
+            .map(|superset| {
+                superset
+                    .requirements()
+                    .iter()
+                    .map(|requirement| self.resolve(requirement))
+                    .collect::<Result<_, _>>()
+                    .map(Superset::new)
+            })
+            .collect::<Result<_, _>>()
+            .map(Block::new)
+    }
+}
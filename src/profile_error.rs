@@ -0,0 +1,17 @@
+use std::path::PathBuf;
+
+use thiserror::Error;
+
+use crate::bar_kind::BarKind;
+
+#[derive(Error, Debug)]
+pub enum ProfileError {
+    #[error("Cannot read lift profile {0}: {1}")]
+    Io(PathBuf, std::io::Error),
+    #[error("Cannot parse lift profile {0}: {1}")]
+    Parse(PathBuf, serde_json::Error),
+    #[error("Invalid bar kind for {0} in the lift profile: {1}")]
+    InvalidKind(String, String),
+    #[error("No max for {0} on a {1} in the lift profile.")]
+    MissingMax(String, BarKind),
+}
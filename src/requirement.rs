@@ -1,22 +1,170 @@
-use std::{fmt::Display, str::FromStr};
+use std::{fmt::Display, str::FromStr, sync::Arc};
 
-use crate::{bar_kind::BarKind, dumbbell::Dumbbell};
+use crate::{
+    bar_kind::BarKind,
+    dumbbell::Dumbbell,
+    rounding::Rounding,
+    unit::{Unit, UnitDisplay, Weight},
+};
 
-#[derive(Debug, Clone, Copy)]
+/// Grams either side of the weights accepted that still meet a requirement, so that weights
+/// converted from pounds aren't missed to rounding.
+const SLACK: u32 = 10;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Requirement {
     weight: u32,
+    heaviest: u32,
     bar_kind: BarKind,
+    handles: usize,
+    rounding: Option<Rounding>,
+    bounds: (u32, u32),
+    label: Option<Arc<str>>,
+    percent: Option<u32>,
+    sets: usize,
+    reps: Option<u32>,
 }
 
 impl Requirement {
     #[must_use]
     pub fn new(weight: u32, bar_kind: BarKind) -> Self {
-        Requirement { weight, bar_kind }
+        Requirement::with_handles(weight, bar_kind, bar_kind.handles())
+    }
+
+    /// A requirement for only `handles` of a bar's handles, e.g. a single dumbbell for
+    /// unilateral work.
+    #[must_use]
+    pub fn with_handles(weight: u32, bar_kind: BarKind, handles: usize) -> Self {
+        Requirement {
+            weight,
+            heaviest: weight,
+            bar_kind,
+            handles,
+            rounding: None,
+            bounds: (weight, weight),
+            label: None,
+            percent: None,
+            sets: 1,
+            reps: None,
+        }
+    }
+
+    /// Asks for `sets` sets of the requirement, optionally of `reps` reps each.
+    #[must_use]
+    pub fn with_sets(self, sets: usize, reps: Option<u32>) -> Self {
+        Requirement {
+            sets: sets.max(1),
+            reps,
+            ..self
+        }
+    }
+
+    /// Names the exercise the requirement is for, which is also the lift its max is looked up
+    /// by.
+    #[must_use]
+    pub fn with_label(self, label: &str) -> Self {
+        Requirement {
+            label: Some(label.into()),
+            ..self
+        }
+    }
+
+    /// Asks for `percent` of the lift's max instead of a set weight, to be worked out with
+    /// [`Requirement::of_max`]. Percentages are kept to the hundredth.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    #[allow(clippy::cast_sign_loss)]
+    pub fn with_percent(self, percent: f64) -> Self {
+        Requirement {
+            percent: Some((percent * 100.0).round() as u32),
+            ..self
+        }
+    }
+
+    /// Works out the weight asked for as a percentage of `max`, to be rounded as the
+    /// requirement or the gym's policy says, or to the nearest weight that can be built if
+    /// neither allows any rounding. Requirements with a set weight are left as they are.
+    #[must_use]
+    pub fn of_max(self, max: u32) -> Self {
+        let Some(percent) = self.percent else {
+            return self;
+        };
+
+        let weight =
+            u32::try_from((u64::from(max) * u64::from(percent) + 5000) / 10000).unwrap_or(u32::MAX);
+        let requirement = Requirement {
+            weight,
+            heaviest: weight,
+            bounds: (weight, weight),
+            ..self
+        };
+
+        match requirement.rounding {
+            Some(rounding) => requirement.with_rounding(rounding),
+            None => requirement,
+        }
+    }
+
+    /// Accepts any weight from the one asked for up to `heaviest`, leaving the cheapest to be
+    /// picked.
+    #[must_use]
+    pub fn up_to(self, heaviest: u32) -> Self {
+        let heaviest = heaviest.max(self.weight);
+
+        Requirement {
+            heaviest,
+            bounds: (self.weight, heaviest),
+            ..self
+        }
+    }
+
+    /// Settles what to do if the weight can't be built exactly, instead of leaving it to the
+    /// gym's policy.
+    #[must_use]
+    pub fn with_rounding(self, rounding: Rounding) -> Self {
+        Requirement {
+            rounding: Some(rounding),
+            bounds: rounding.bounds(self.range(), &[]).unwrap_or(self.bounds),
+            ..self
+        }
+    }
+
+    /// Accepts the weights allowed by its rounding, or by `default` if it has none, out of the
+    /// sorted weights that can be built for it. Percentages of a max are rarely buildable
+    /// exactly, so they round to the nearest weight when `default` is exact.
+    #[must_use]
+    pub fn rounded(self, default: Rounding, achievable: &[u32]) -> Self {
+        let rounding = match (self.rounding, default) {
+            (Some(rounding), _) => rounding,
+            (None, Rounding::Exact) if self.percent.is_some() => Rounding::Nearest,
+            (None, default) => default,
+        };
+        let achievable = achievable
+            .iter()
+            .map(|weight| {
+                if self.offset(*weight) == 0 {
+                    (*weight).clamp(self.weight, self.heaviest)
+                } else {
+                    *weight
+                }
+            })
+            .collect::<Vec<_>>();
+
+        Requirement {
+            rounding: Some(rounding),
+            bounds: rounding
+                .bounds(self.range(), &achievable)
+                .unwrap_or(self.range()),
+            ..self
+        }
     }
 
     #[must_use]
     pub fn matches(&self, dumbbell: &Dumbbell) -> bool {
-        self.weight == dumbbell.weight() && self.bar_kind == *dumbbell.bar().kind()
+        (self.bounds.0.saturating_sub(SLACK)..=self.bounds.1.saturating_add(SLACK))
+            .contains(&dumbbell.weight())
+            && self.bar_kind == *dumbbell.bar().kind()
+            && self.handles <= dumbbell.handles()
     }
 
     #[must_use]
@@ -24,37 +172,214 @@ impl Requirement {
         self.bar_kind
     }
 
+    /// The weight asked for, or the lightest one for a range.
     #[must_use]
     pub fn weight(&self) -> u32 {
         self.weight
     }
+
+    /// The lightest and heaviest weights asked for, before any rounding.
+    #[must_use]
+    pub fn range(&self) -> (u32, u32) {
+        (self.weight, self.heaviest)
+    }
+
+    #[must_use]
+    pub fn handles(&self) -> usize {
+        self.handles
+    }
+
+    #[must_use]
+    pub fn rounding(&self) -> Option<Rounding> {
+        self.rounding
+    }
+
+    /// How many grams `weight` is over or, if negative, under the weights asked for, with
+    /// anything close enough to pass for them as zero.
+    #[must_use]
+    pub fn offset(&self, weight: u32) -> i64 {
+        let asked = weight.clamp(self.weight, self.heaviest);
+
+        if weight.abs_diff(asked) <= SLACK {
+            0
+        } else {
+            i64::from(weight) - i64::from(asked)
+        }
+    }
+
+    #[must_use]
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    #[must_use]
+    pub fn sets(&self) -> usize {
+        self.sets
+    }
+
+    #[must_use]
+    pub fn reps(&self) -> Option<u32> {
+        self.reps
+    }
+
+    /// The percentage of the lift's max asked for, if any.
+    #[must_use]
+    pub fn percent(&self) -> Option<f64> {
+        self.percent.map(|percent| f64::from(percent) / 100.0)
+    }
+
+    /// The lightest and heaviest weights that meet the requirement.
+    #[must_use]
+    pub fn bounds(&self) -> (u32, u32) {
+        self.bounds
+    }
+
+    /// Parses sets and reps, e.g. `3x10`, or just sets, e.g. `3`.
+    fn parse_scheme(scheme: &str) -> Result<(usize, Option<u32>), String> {
+        let invalid = || "Invalid sets and reps, e.g. 3x10".to_string();
+        let (sets, reps) = match scheme.trim().split_once(['x', 'X']) {
+            Some((sets, reps)) => (sets, Some(reps.trim().parse().map_err(|_| invalid())?)),
+            None => (scheme, None),
+        };
+
+        match sets.trim().parse() {
+            Ok(0) | Err(_) => Err(invalid()),
+            Ok(sets) => Ok((sets, reps)),
+        }
+    }
 }
 
 impl FromStr for Requirement {
     type Err = String;
 
+    /// Parses a weight followed by a bar kind, e.g. `22.5d` or `45lbd`, in kg unless it says
+    /// otherwise, or a range of weights, e.g. `20-24d`, optionally followed by a tolerance
+    /// either side, e.g. `60b~2.5`, or how to round it, e.g. `23d/nearest`. The exercise can be
+    /// named before a colon, e.g. `bench:60b`, which lets the weight be a percentage of its
+    /// max, e.g. `bench:80%b`, and sets and reps can be given before an at sign, e.g.
+    /// `incline:3x10@22.5d` or `3@22.5d`.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (weight, bar_kind) = s.split_at(s.len() - 1);
-        let weight = weight
-            .parse::<f64>()
-            .map_err(|_| "Invalid weight".to_string())?;
-        let bar_kind = BarKind::from_str(bar_kind.to_lowercase().as_str())?;
-
-        Ok(Requirement {
-            weight: kgs_to_grams(weight),
-            bar_kind,
+        let (label, s) = match s.split_once(':') {
+            Some((label, s)) if !label.trim().is_empty() => (Some(label.trim()), s),
+            Some(_) => return Err("Missing exercise name".to_string()),
+            None => (None, s),
+        };
+
+        let (scheme, s) = match s.split_once('@') {
+            Some((scheme, s)) => (Some(Self::parse_scheme(scheme)?), s),
+            None => (None, s),
+        };
+
+        let (s, rounding) = match s.split_once('/') {
+            Some((s, rounding)) => (s, Some(rounding.parse::<Rounding>()?)),
+            None => (s, None),
+        };
+
+        let (s, rounding) = match s.split_once('~') {
+            Some((s, tolerance)) => match tolerance.parse::<Rounding>()? {
+                tolerance @ Rounding::Within(_) if rounding.is_none() => (s, Some(tolerance)),
+                _ => return Err("Invalid tolerance".to_string()),
+            },
+            None => (s, rounding),
+        };
+
+        let Some((split, _)) = s.char_indices().last() else {
+            return Err("Missing weight and bar kind".to_string());
+        };
+        let (weights, bar_kind) = s.split_at(split);
+        let (weights, percent) = match weights.strip_suffix('%') {
+            Some(percent) => (
+                "0",
+                Some(
+                    percent
+                        .parse::<f64>()
+                        .ok()
+                        .filter(|percent| *percent >= 0.0 && label.is_some())
+                        .ok_or_else(|| "Invalid percentage of a lift's max".to_string())?,
+                ),
+            ),
+            None => (weights, None),
+        };
+        let (weights, unit) = Unit::split(weights);
+        let (lightest, heaviest) = weights.split_once('-').unwrap_or((weights, weights));
+        let [lightest, heaviest] =
+            [lightest, heaviest].map(|weight| Unit::parse_weight(weight, unit.unwrap_or_default()));
+        let (lightest, heaviest) = (lightest?, heaviest?);
+
+        if heaviest < lightest {
+            return Err("Invalid weight range".to_string());
+        }
+
+        let (bar_kind, handles) = match bar_kind.to_lowercase().as_str() {
+            "s" => (BarKind::Dumbbell, 1),
+            bar_kind => {
+                let bar_kind = BarKind::from_str(bar_kind)?;
+                (bar_kind, bar_kind.handles())
+            }
+        };
+
+        let mut requirement =
+            Requirement::with_handles(lightest, bar_kind, handles).up_to(heaviest);
+
+        if let Some(label) = label {
+            requirement = requirement.with_label(label);
+        }
+
+        if let Some(percent) = percent {
+            requirement = requirement.with_percent(percent);
+        }
+
+        if let Some((sets, reps)) = scheme {
+            requirement = requirement.with_sets(sets, reps);
+        }
+
+        Ok(match rounding {
+            Some(rounding) => requirement.with_rounding(rounding),
+            None => requirement,
         })
     }
 }
 
-impl Display for Requirement {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}kg {}", f64::from(self.weight) / 1000.0, self.bar_kind)
+impl UnitDisplay for Requirement {
+    fn fmt_in(&self, f: &mut std::fmt::Formatter<'_>, unit: Unit) -> std::fmt::Result {
+        if let Some(label) = &self.label {
+            write!(f, "{label}: ")?;
+        }
+
+        if let Some(percent) = self.percent() {
+            write!(f, "{percent}% ")?;
+
+            if self.weight > 0 {
+                write!(f, "= ")?;
+            }
+        }
+
+        if self.percent.is_none() || self.weight > 0 {
+            if self.heaviest > self.weight {
+                write!(f, "{}-", unit.value(self.weight))?;
+            }
+
+            write!(f, "{} ", Weight(self.heaviest).in_unit(unit))?;
+        }
+
+        if self.handles < self.bar_kind.handles() {
+            write!(f, "single ")?;
+        }
+
+        write!(f, "{}", self.bar_kind)?;
+
+        match self.rounding {
+            None | Some(Rounding::Exact) => Ok(()),
+            Some(Rounding::Within(tolerance)) => {
+                write!(f, " (within {})", Weight(tolerance).in_unit(unit))
+            }
+            Some(rounding) => write!(f, " ({})", rounding.in_unit(unit)),
+        }
     }
 }
 
-#[allow(clippy::cast_possible_truncation)]
-#[allow(clippy::cast_sign_loss)]
-fn kgs_to_grams(kgs: f64) -> u32 {
-    (kgs * 1000.0) as u32
+impl Display for Requirement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.fmt_in(f, Unit::Kg)
+    }
 }
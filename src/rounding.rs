@@ -0,0 +1,103 @@
+use std::{fmt::Display, str::FromStr};
+
+use crate::unit::{Unit, UnitDisplay, Weight};
+
+/// What to do when a requirement's weight can't be built exactly.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Rounding {
+    /// Only the exact weight will do.
+    #[default]
+    Exact,
+    /// The closest weight that can be built, either side.
+    Nearest,
+    /// The heaviest weight that can be built without going over.
+    Down,
+    /// The lightest weight that can be built without going under.
+    Up,
+    /// Any weight that can be built within this many grams either side.
+    Within(u32),
+}
+
+impl Rounding {
+    /// The lightest and heaviest weights accepted when asking for anything from `lightest` to
+    /// `heaviest`, given the sorted weights that can be built, or `None` if none of them will
+    /// do. Weights that can be built in range are always accepted.
+    #[must_use]
+    pub fn bounds(
+        &self,
+        (lightest, heaviest): (u32, u32),
+        achievable: &[u32],
+    ) -> Option<(u32, u32)> {
+        let in_range = achievable
+            .iter()
+            .any(|achievable| (lightest..=heaviest).contains(achievable));
+
+        match self {
+            Rounding::Exact => Some((lightest, heaviest)),
+            Rounding::Within(tolerance) => Some((
+                lightest.saturating_sub(*tolerance),
+                heaviest.saturating_add(*tolerance),
+            )),
+            _ if in_range => Some((lightest, heaviest)),
+            Rounding::Down => achievable
+                .iter()
+                .rev()
+                .find(|achievable| **achievable < lightest)
+                .map(|achievable| (*achievable, *achievable)),
+            Rounding::Up => achievable
+                .iter()
+                .find(|achievable| **achievable > heaviest)
+                .map(|achievable| (*achievable, *achievable)),
+            Rounding::Nearest => achievable
+                .iter()
+                .map(|achievable| {
+                    achievable
+                        .abs_diff(lightest)
+                        .min(achievable.abs_diff(heaviest))
+                })
+                .min()
+                .map(|distance| {
+                    (
+                        lightest.saturating_sub(distance),
+                        heaviest.saturating_add(distance),
+                    )
+                }),
+        }
+    }
+}
+
+impl UnitDisplay for Rounding {
+    fn fmt_in(&self, f: &mut std::fmt::Formatter<'_>, unit: Unit) -> std::fmt::Result {
+        match self {
+            Rounding::Exact => write!(f, "exact"),
+            Rounding::Nearest => write!(f, "nearest"),
+            Rounding::Down => write!(f, "down"),
+            Rounding::Up => write!(f, "up"),
+            Rounding::Within(tolerance) => write!(f, "{}", Weight(*tolerance).in_unit(unit)),
+        }
+    }
+}
+
+impl Display for Rounding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.fmt_in(f, Unit::Kg)
+    }
+}
+
+/// Parses `exact`, `nearest`, `down`, `up`, or a tolerance either side in kg unless it says
+/// otherwise, e.g. `2.5` or `5lb`.
+impl FromStr for Rounding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "exact" => Ok(Rounding::Exact),
+            "nearest" => Ok(Rounding::Nearest),
+            "down" => Ok(Rounding::Down),
+            "up" => Ok(Rounding::Up),
+            tolerance => Unit::parse_weight(tolerance, Unit::Kg)
+                .map(Rounding::Within)
+                .map_err(|_| "Invalid rounding.".to_string()),
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use std::{fs, io::ErrorKind, path::Path};
+
+use crate::{
+    bar::Bar, bar_loading::BarLoading, gym_state::GymState, saved_state_error::SavedStateError,
+};
+
+/// What was left on the bars at the end of the last session, kept alongside the inventory so
+/// the next session can start from it. Bars that aren't listed are bare.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SavedState {
+    loadings: Vec<BarLoading>,
+}
+
+impl SavedState {
+    #[must_use]
+    pub fn new(loadings: Vec<BarLoading>) -> Self {
+        SavedState { loadings }
+    }
+
+    /// Records every loaded bar in `state`, leaving out the bare ones.
+    #[must_use]
+    pub fn from_state(state: &GymState, bars: &[Bar]) -> Self {
+        SavedState::new(
+            state
+                .iter()
+                .filter(|(_, dumbbell)| !dumbbell.plates().is_empty())
+                .map(|(_, dumbbell)| BarLoading::from_dumbbell(dumbbell, bars))
+                .collect(),
+        )
+    }
+
+    /// Reads the state saved at `path`, or `None` if nothing has been saved yet.
+    ///
+    /// # Errors
+    /// If the file exists but cannot be read or parsed.
+    ///
+    pub fn load(path: &Path) -> Result<Option<Self>, SavedStateError> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(SavedStateError::Io(path.to_path_buf(), e)),
+        };
+
+        serde_json::from_str(&contents)
+            .map(|loadings| Some(SavedState::new(loadings)))
+            .map_err(|e| SavedStateError::Parse(path.to_path_buf(), e))
+    }
+
+    ///
+    /// # Errors
+    /// If the state cannot be serialized or the file cannot be written.
+    ///
+    pub fn save(&self, path: &Path) -> Result<(), SavedStateError> {
+        let contents = serde_json::to_string_pretty(&self.loadings)
+            .map_err(|e| SavedStateError::Serialize(path.to_path_buf(), e))?;
+
+        fs::write(path, contents + "\n").map_err(|e| SavedStateError::Io(path.to_path_buf(), e))
+    }
+
+    #[must_use]
+    pub fn loadings(&self) -> &[BarLoading] {
+        &self.loadings
+    }
+
+    /// Loads the saved plates on the matching bars among `bars`.
+    ///
+    /// # Errors
+    /// If a saved loading refers to a bar that isn't in `bars`.
+    ///
+    pub fn state(&self, bars: &[Bar]) -> Result<GymState, String> {
+        self.loadings
+            .iter()
+            .map(|loading| {
+                let dumbbell = loading.resolve(bars)?;
+                Ok((dumbbell.bar().clone(), dumbbell))
+            })
+            .collect::<Result<_, String>>()
+            .map(GymState::new)
+    }
+}
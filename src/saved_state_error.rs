@@ -0,0 +1,13 @@
+use std::path::PathBuf;
+
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum SavedStateError {
+    #[error("Cannot access state file {0}: {1}")]
+    Io(PathBuf, std::io::Error),
+    #[error("Cannot parse state file {0}: {1}")]
+    Parse(PathBuf, serde_json::Error),
+    #[error("Cannot serialize state for {0}: {1}")]
+    Serialize(PathBuf, serde_json::Error),
+}
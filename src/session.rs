@@ -0,0 +1,189 @@
+use std::{
+    fs,
+    io::{self, Read},
+    path::Path,
+};
+
+use serde::Deserialize;
+
+use crate::{
+    block::Block, requirement::Requirement, session_error::SessionError, superset::Superset,
+};
+
+/// A requirement in a JSON session, either in the command line syntax or as an exercise.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum RequirementEntry {
+    Text(String),
+    Exercise(ExerciseEntry),
+}
+
+/// A superset in a JSON session: a requirement, or requirements loaded at the same time.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum SupersetEntry {
+    Requirement(RequirementEntry),
+    Requirements(Vec<RequirementEntry>),
+}
+
+/// A block in a JSON session: a requirement, or supersets done in any order.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum BlockEntry {
+    Requirement(RequirementEntry),
+    Supersets(Vec<SupersetEntry>),
+}
+
+#[derive(Debug, Deserialize)]
+struct ExerciseEntry {
+    #[serde(default)]
+    exercise: Option<String>,
+    #[serde(default)]
+    sets: Option<usize>,
+    #[serde(default)]
+    reps: Option<u32>,
+    weight: String,
+}
+
+/// A session written down ahead of time, in either of two formats.
+///
+/// As text, each line is a block in the same syntax as the command line, e.g.
+/// `incline press:3x10@22.5d` or `curl:10-12e,row:3@21d`, so exercise names can have spaces in
+/// them. Blank lines and anything after a `#` are left out.
+///
+/// As JSON, a list of blocks, where a block is a requirement or a list of supersets done in
+/// any order, and a superset is a requirement or a list of requirements loaded at the same
+/// time. Requirements are in the command line syntax or exercises like `{ "exercise":
+/// "incline", "sets": 3, "reps": 10, "weight": "22.5d" }`, where only the weight is needed and
+/// the other fields override what the weight says, e.g.
+/// `["incline:3x10@22.5d", ["curl:2x12@10-12e", "row:3@21d"], [["press:16d", "squat:60b"]]]`.
+#[derive(Clone, Debug, Default)]
+pub struct Session {
+    blocks: Vec<Block>,
+}
+
+impl Session {
+    #[must_use]
+    pub fn new(blocks: Vec<Block>) -> Self {
+        Session { blocks }
+    }
+
+    /// Reads the session at `path`, or from standard input if it is `-`, as JSON if it starts
+    /// with a bracket and as text otherwise.
+    ///
+    /// # Errors
+    /// If the session cannot be read or parsed.
+    ///
+    pub fn read(path: &Path) -> Result<Self, SessionError> {
+        let contents = if path == Path::new("-") {
+            let mut contents = String::new();
+            io::stdin().read_to_string(&mut contents).map(|_| contents)
+        } else {
+            fs::read_to_string(path)
+        }
+        .map_err(|e| SessionError::Io(path.to_path_buf(), e))?;
+
+        if contents.trim_start().starts_with('[') {
+            Self::from_json(path, &contents)
+        } else {
+            Self::from_text(path, &contents)
+        }
+    }
+
+    #[must_use]
+    pub fn blocks(&self) -> &[Block] {
+        &self.blocks
+    }
+
+    fn from_text(path: &Path, contents: &str) -> Result<Self, SessionError> {
+        contents
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i, line.split('#').next().unwrap_or_default().trim()))
+            .filter(|(_, line)| !line.is_empty())
+            .map(|(i, line)| {
+                line.parse()
+                    .map_err(|e| SessionError::Line(path.to_path_buf(), i + 1, e))
+            })
+            .collect::<Result<_, _>>()
+            .map(Session::new)
+    }
+
+    fn from_json(path: &Path, contents: &str) -> Result<Self, SessionError> {
+        serde_json::from_str::<Vec<BlockEntry>>(contents)
+            .map_err(|e| SessionError::Json(path.to_path_buf(), e))?
+            .into_iter()
+            .map(BlockEntry::block)
+            .collect::<Result<_, _>>()
+            .map(Session::new)
+            .map_err(|e| SessionError::Entry(path.to_path_buf(), e))
+    }
+}
+
+impl RequirementEntry {
+    fn requirement(self) -> Result<Requirement, String> {
+        match self {
+            RequirementEntry::Text(text) => text.parse(),
+            RequirementEntry::Exercise(exercise) => exercise.requirement(),
+        }
+    }
+}
+
+impl ExerciseEntry {
+    /// Only the fields the entry gives override what its weight says, and an exercise named in
+    /// both has to be the same.
+    fn requirement(self) -> Result<Requirement, String> {
+        if self.sets == Some(0) {
+            return Err("Invalid sets, expected at least one".to_string());
+        }
+
+        // The weight is parsed with the exercise name in front, so that it can be a percentage
+        // of the exercise's max.
+        let weight = match (&self.exercise, self.weight.split_once(':')) {
+            (Some(exercise), Some((label, _))) if exercise.trim() != label.trim() => {
+                return Err(format!(
+                    "Exercise {exercise} doesn't match {} in its weight",
+                    label.trim()
+                ));
+            }
+            (Some(exercise), None) => format!("{exercise}:{}", self.weight),
+            _ => self.weight,
+        };
+        let requirement = weight.parse::<Requirement>()?;
+
+        let sets = self.sets.unwrap_or(requirement.sets());
+        let reps = self.reps.or(requirement.reps());
+
+        Ok(requirement.with_sets(sets, reps))
+    }
+}
+
+impl SupersetEntry {
+    fn superset(self) -> Result<Superset, String> {
+        match self {
+            SupersetEntry::Requirement(RequirementEntry::Text(text)) => text.parse(),
+            SupersetEntry::Requirement(requirement) => {
+                requirement.requirement().map(Superset::from)
+            }
+            SupersetEntry::Requirements(requirements) => requirements
+                .into_iter()
+                .map(RequirementEntry::requirement)
+                .collect::<Result<_, _>>()
+                .map(Superset::new),
+        }
+    }
+}
+
+impl BlockEntry {
+    fn block(self) -> Result<Block, String> {
+        match self {
+            BlockEntry::Requirement(RequirementEntry::Text(text)) => text.parse(),
+            BlockEntry::Requirement(requirement) => requirement.requirement().map(Block::from),
+            BlockEntry::Supersets(supersets) => supersets
+                .into_iter()
+                .map(SupersetEntry::superset)
+                .collect::<Result<_, _>>()
+                .map(Block::new),
+        }
+    }
+}
@@ -0,0 +1,15 @@
+use std::path::PathBuf;
+
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum SessionError {
+    #[error("Cannot read session {0}: {1}")]
+    Io(PathBuf, std::io::Error),
+    #[error("Cannot parse session {0}: {1}")]
+    Json(PathBuf, serde_json::Error),
+    #[error("Invalid session {0}, line {1}: {2}")]
+    Line(PathBuf, usize, String),
+    #[error("Invalid session {0}: {1}")]
+    Entry(PathBuf, String),
+}
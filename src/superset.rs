@@ -0,0 +1,146 @@
+use std::{fmt::Display, str::FromStr};
+
+use itertools::Itertools;
+
+use crate::{
+    dumbbell::Dumbbell,
+    requirement::Requirement,
+    unit::{Unit, UnitDisplay},
+};
+
+/// Requirements that have to be loaded at the same time, each on a bar of its own, e.g. for
+/// supersets and circuits.
+#[derive(Clone, Debug)]
+pub struct Superset {
+    requirements: Vec<Requirement>,
+    set: usize,
+}
+
+impl Superset {
+    #[must_use]
+    pub fn new(requirements: Vec<Requirement>) -> Self {
+        Superset {
+            requirements,
+            set: 1,
+        }
+    }
+
+    /// Numbers the superset as the `set`th set of its requirements, starting from 1.
+    #[must_use]
+    pub fn with_set(self, set: usize) -> Self {
+        Superset { set, ..self }
+    }
+
+    #[must_use]
+    pub fn requirements(&self) -> &[Requirement] {
+        &self.requirements
+    }
+
+    /// Which set of its requirements the superset is, starting from 1.
+    #[must_use]
+    pub fn set(&self) -> usize {
+        self.set
+    }
+
+    /// One superset per set, each with the requirements that still have that set to do and
+    /// numbered as that set, e.g. three rounds of a circuit.
+    #[must_use]
+    pub fn sets(&self) -> Vec<Superset> {
+        let sets = self
+            .requirements
+            .iter()
+            .map(Requirement::sets)
+            .max()
+            .unwrap_or(1);
+
+        (1..=sets)
+            .map(|set| {
+                Superset::new(
+                    self.requirements
+                        .iter()
+                        .filter(|requirement| requirement.sets() >= set)
+                        .cloned()
+                        .collect(),
+                )
+                .with_set(set)
+            })
+            .collect()
+    }
+
+    /// Picks a different loaded bar out of `dumbbells` for each requirement, in the order the
+    /// requirements are given, or `None` if they can't all be met at once.
+    #[must_use]
+    pub fn assign<'a>(&self, dumbbells: &[&'a Dumbbell]) -> Option<Vec<&'a Dumbbell>> {
+        let mut used = vec![false; dumbbells.len()];
+        let mut assigned = Vec::with_capacity(self.requirements.len());
+
+        Self::assign_from(&self.requirements, dumbbells, &mut used, &mut assigned)
+            .then_some(assigned)
+    }
+
+    fn assign_from<'a>(
+        requirements: &[Requirement],
+        dumbbells: &[&'a Dumbbell],
+        used: &mut [bool],
+        assigned: &mut Vec<&'a Dumbbell>,
+    ) -> bool {
+        let Some((requirement, rest)) = requirements.split_first() else {
+            return true;
+        };
+
+        for (i, dumbbell) in dumbbells.iter().enumerate() {
+            if used[i] || !requirement.matches(dumbbell) {
+                continue;
+            }
+
+            used[i] = true;
+            assigned.push(dumbbell);
+
+            if Self::assign_from(rest, dumbbells, used, assigned) {
+                return true;
+            }
+
+            used[i] = false;
+            assigned.pop();
+        }
+
+        false
+    }
+}
+
+impl From<Requirement> for Superset {
+    fn from(requirement: Requirement) -> Self {
+        Superset::new(vec![requirement])
+    }
+}
+
+/// Parses requirements joined by pluses, e.g. `22.5d+60b`.
+impl FromStr for Superset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split('+')
+            .map(|requirement| requirement.trim().parse())
+            .collect::<Result<Vec<_>, _>>()
+            .map(Superset::new)
+    }
+}
+
+impl UnitDisplay for Superset {
+    fn fmt_in(&self, f: &mut std::fmt::Formatter<'_>, unit: Unit) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            self.requirements
+                .iter()
+                .map(|requirement| requirement.in_unit(unit))
+                .join(" + ")
+        )
+    }
+}
+
+impl Display for Superset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.fmt_in(f, Unit::Kg)
+    }
+}
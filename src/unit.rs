@@ -0,0 +1,134 @@
+use std::{fmt::Display, str::FromStr};
+
+use serde::Deserialize;
+
+/// Grams in a pound.
+const GRAMS_PER_POUND: f64 = 453.592_37;
+
+/// A unit weights can be given or displayed in. Weights are always kept in grams, so pound
+/// weights are rounded to the nearest gram.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Unit {
+    #[default]
+    Kg,
+    Lb,
+}
+
+impl Unit {
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    #[allow(clippy::cast_sign_loss)]
+    pub fn grams(self, value: f64) -> u32 {
+        match self {
+            Unit::Kg => (value * 1000.0).round() as u32,
+            Unit::Lb => (value * GRAMS_PER_POUND).round() as u32,
+        }
+    }
+
+    /// The weight in this unit, to the nearest gram for kilograms and the nearest hundredth
+    /// for pounds.
+    #[must_use]
+    pub fn value(self, grams: u32) -> f64 {
+        match self {
+            Unit::Kg => f64::from(grams) / 1000.0,
+            Unit::Lb => (f64::from(grams) / GRAMS_PER_POUND * 100.0).round() / 100.0,
+        }
+    }
+
+    /// Parses a weight followed by an optional unit, e.g. `22.5`, `20kg` or `45lb`, taking it
+    /// to be in `default` if none is given.
+    ///
+    /// # Errors
+    /// If the weight isn't a number or is negative.
+    ///
+    pub fn parse_weight(s: &str, default: Self) -> Result<u32, String> {
+        let (value, unit) = Self::split(s);
+
+        value
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|value| *value >= 0.0)
+            .map(|value| unit.unwrap_or(default).grams(value))
+            .ok_or_else(|| "Invalid weight".to_string())
+    }
+
+    /// Splits a unit off the end of `s`, if it has one.
+    #[must_use]
+    pub fn split(s: &str) -> (&str, Option<Self>) {
+        [("kg", Unit::Kg), ("lb", Unit::Lb)]
+            .into_iter()
+            .find_map(|(suffix, unit)| s.strip_suffix(suffix).map(|s| (s, Some(unit))))
+            .unwrap_or((s, None))
+    }
+}
+
+impl Display for Unit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Unit::Kg => write!(f, "kg"),
+            Unit::Lb => write!(f, "lb"),
+        }
+    }
+}
+
+impl FromStr for Unit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "kg" => Ok(Unit::Kg),
+            "lb" => Ok(Unit::Lb),
+            _ => Err("Invalid unit.".to_string()),
+        }
+    }
+}
+
+/// A weight from a data file, in grams unless the entry gives its `unit`.
+#[allow(clippy::cast_possible_truncation)]
+#[allow(clippy::cast_sign_loss)]
+pub(crate) fn entry_grams(weight: f64, unit: Option<Unit>) -> u32 {
+    unit.map_or(weight.round() as u32, |unit| unit.grams(weight))
+}
+
+/// Displays weights in a given unit, e.g. for the plans and loadings they appear in. `Display`
+/// shows them in kg.
+pub trait UnitDisplay {
+    /// Writes `self` with every weight in `unit`.
+    ///
+    /// # Errors
+    /// If the formatter fails.
+    ///
+    fn fmt_in(&self, f: &mut std::fmt::Formatter<'_>, unit: Unit) -> std::fmt::Result;
+
+    /// Displays `self` with every weight in `unit`.
+    fn in_unit(&self, unit: Unit) -> InUnit<'_, Self> {
+        InUnit(self, unit)
+    }
+}
+
+/// Something displayed with every weight in the given unit, see [`UnitDisplay::in_unit`].
+pub struct InUnit<'a, T: ?Sized>(&'a T, Unit);
+
+impl<T: UnitDisplay + ?Sized> Display for InUnit<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt_in(f, self.1)
+    }
+}
+
+/// A weight in grams, displayed with its unit, e.g. `22.5kg`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Weight(pub u32);
+
+impl UnitDisplay for Weight {
+    fn fmt_in(&self, f: &mut std::fmt::Formatter<'_>, unit: Unit) -> std::fmt::Result {
+        write!(f, "{}{unit}", unit.value(self.0))
+    }
+}
+
+impl Display for Weight {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.fmt_in(f, Unit::Kg)
+    }
+}
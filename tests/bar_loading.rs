@@ -0,0 +1,53 @@
+use workout_rs::{bar::Bar, bar_kind::BarKind, bar_loading::BarLoading};
+
+#[test]
+fn parses_kind_position_and_plates() {
+    let loading = "d2:2.5,1.25".parse::<BarLoading>().unwrap();
+
+    assert_eq!(loading.bar(), "d2");
+    assert_eq!(loading.plates(), [2500, 1250]);
+    assert!("b:".parse::<BarLoading>().unwrap().plates().is_empty());
+    assert!("b20".parse::<BarLoading>().is_err());
+}
+
+#[test]
+fn resolves_bars_by_name_or_kind() {
+    let bars = [
+        Bar::new(2000, 1, BarKind::Dumbbell),
+        Bar::new(2000, 1, BarKind::Dumbbell)
+            .with_id(1)
+            .with_name("blue handle"),
+        Bar::new(15000, 2, BarKind::Barbell),
+    ];
+
+    let named = "blue handle:1.25".parse::<BarLoading>().unwrap();
+    assert_eq!(named.resolve(&bars).unwrap().bar(), &bars[1]);
+
+    let named_single = "s:blue handle:1.25".parse::<BarLoading>().unwrap();
+    assert_eq!(named_single.bar(), "s:blue handle");
+    let dumbbell = named_single.resolve(&bars).unwrap();
+    assert_eq!(dumbbell.bar(), &bars[1]);
+    assert_eq!(dumbbell.handles(), 1);
+
+    let single = "s2:1.25".parse::<BarLoading>().unwrap();
+    let dumbbell = single.resolve(&bars).unwrap();
+    assert_eq!(dumbbell.bar(), &bars[1]);
+    assert_eq!(dumbbell.handles(), 1);
+
+    assert_eq!(
+        "b:20"
+            .parse::<BarLoading>()
+            .unwrap()
+            .resolve(&bars)
+            .unwrap()
+            .weight(),
+        55000
+    );
+    assert!(
+        "b2:20"
+            .parse::<BarLoading>()
+            .unwrap()
+            .resolve(&bars)
+            .is_err()
+    );
+}
@@ -0,0 +1,79 @@
+use std::{
+    path::PathBuf,
+    process::{Command as Process, Output},
+};
+
+use clap::Parser;
+use workout_rs::{
+    args::{Args, Command, StateCommand},
+    unit::Unit,
+};
+
+fn run(args: &[&str]) -> Output {
+    let state = std::env::temp_dir().join(format!("workout-cli-state-{}", std::process::id()));
+
+    Process::new(env!("CARGO_BIN_EXE_workout-rs"))
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .args(args)
+        .arg("--no-save")
+        .arg("--state")
+        .arg(state)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn documented_examples_plan_with_the_shipped_inventory() {
+    let examples = [
+        "22.5d 60b",
+        "22.5d+60b",
+        "10d,12.5d",
+        "20-24d",
+        "60b~2.5",
+        "incline:3x10@22.5d",
+    ];
+
+    for example in examples {
+        let output = run(&example.split(' ').collect::<Vec<_>>());
+
+        assert!(
+            output.status.success(),
+            "{example}: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+}
+
+#[test]
+fn global_options_can_come_before_a_subcommand() {
+    let args = Args::try_parse_from(["workout-rs", "--state", "/tmp/s.json", "state", "show"])
+        .unwrap()
+        .checked()
+        .unwrap();
+
+    assert!(matches!(
+        args.command,
+        Some(Command::State(StateCommand::Show))
+    ));
+    assert_eq!(args.state, PathBuf::from("/tmp/s.json"));
+
+    let args = Args::try_parse_from(["workout-rs", "--unit", "lb", "warm-up", "100b"]).unwrap();
+    assert!(matches!(args.command, Some(Command::WarmUp { .. })));
+    assert_eq!(args.unit, Unit::Lb);
+}
+
+#[test]
+fn requirements_are_not_taken_along_with_a_subcommand() {
+    let args = Args::try_parse_from(["workout-rs", "60b", "state", "show"]).unwrap();
+
+    assert!(args.checked().is_err());
+}
+
+#[test]
+fn warm_up_steps_are_bounded() {
+    assert!(Args::try_parse_from(["workout-rs", "warm-up", "100b", "--steps", "20"]).is_ok());
+
+    for steps in ["21", "18446744073709551615"] {
+        assert!(Args::try_parse_from(["workout-rs", "warm-up", "100b", "--steps", steps]).is_err());
+    }
+}
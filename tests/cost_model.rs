@@ -0,0 +1,79 @@
+use std::{collections::HashMap, str::FromStr};
+
+use workout_rs::{
+    bar::Bar,
+    bar_kind::BarKind,
+    collar::Collar,
+    cost_model::{CostModel, CostModelKind, PlateCountCost, PlateWeightCost, UnitCost},
+    dumbbell::Dumbbell,
+    gym::Gym,
+    gym_state::GymState,
+    plate::Plate,
+    requirement::Requirement,
+};
+
+#[test]
+fn models_weigh_plates_and_collars_differently() {
+    let bar = Bar::new(15000, 2, BarKind::Barbell);
+    let plate = Plate::new(20000, 2);
+    let collar = Collar::new(500, 2);
+
+    assert_eq!(UnitCost.plate_cost(&bar, &plate), 1);
+    assert_eq!(PlateWeightCost.plate_cost(&bar, &plate), 40000);
+    assert_eq!(PlateCountCost.plate_cost(&bar, &plate), 2);
+
+    assert_eq!(UnitCost.collar_cost(&bar, &collar), 1);
+    assert_eq!(PlateWeightCost.collar_cost(&bar, &collar), 1000);
+    assert_eq!(PlateCountCost.collar_cost(&bar, &collar), 2);
+}
+
+#[test]
+fn models_price_the_same_change_differently() {
+    let bar = Bar::new(2000, 1, BarKind::Dumbbell);
+    let from = Dumbbell::new(Plate::from_weights(vec![2500, 500], 1), bar.clone());
+    let to = Dumbbell::new(Plate::from_weights(vec![2500, 1250, 500], 1), bar);
+
+    assert_eq!(from.change_cost(&to, &UnitCost), Some(6));
+    assert_eq!(from.change_cost(&to, &PlateWeightCost), Some(9000));
+    assert_eq!(from.change_cost(&to, &PlateCountCost), Some(12));
+}
+
+#[test]
+fn models_pick_different_plans() {
+    let bar = Bar::new(15000, 2, BarKind::Barbell);
+    let plates = Plate::from_weights_map(HashMap::from([(20000, 2), (2500, 4)]), 2);
+    let start = GymState::new(HashMap::from([(
+        bar.clone(),
+        Dumbbell::new(vec![], bar.clone()),
+    )]));
+    let requirement = Requirement::new(22500, BarKind::Barbell).up_to(55000);
+
+    let weight = |cost_model: &dyn CostModel| {
+        let gym = Gym::new(&plates, std::slice::from_ref(&bar), cost_model);
+        let plan = gym
+            .order_with(Some(&start), &[requirement.clone().into()], false)
+            .unwrap();
+        plan.steps()[0].dumbbells()[0].weight()
+    };
+
+    assert_eq!(weight(&UnitCost), 55000);
+    assert_eq!(weight(&PlateCountCost), 55000);
+    assert_eq!(weight(&PlateWeightCost), 25000);
+}
+
+#[test]
+fn kinds_are_parsed_by_name() {
+    for kind in [
+        CostModelKind::Unit,
+        CostModelKind::PlateWeight,
+        CostModelKind::PlateCount,
+    ] {
+        assert_eq!(CostModelKind::from_str(&kind.to_string()), Ok(kind));
+    }
+
+    assert_eq!(
+        CostModelKind::from_str("weight"),
+        Ok(CostModelKind::PlateWeight)
+    );
+    assert!(CostModelKind::from_str("plates").is_err());
+}
@@ -0,0 +1,102 @@
+use itertools::Itertools;
+use workout_rs::{
+    bar::Bar,
+    bar_kind::BarKind,
+    collar::Collar,
+    cost_model::{PlateCountCost, UnitCost},
+    dumbbell::Dumbbell,
+    plate::Plate,
+};
+
+fn dumbbell(weights: Vec<u32>) -> Dumbbell {
+    Dumbbell::new(
+        Plate::from_weights(weights, 2),
+        Bar::new(15000, 2, BarKind::Barbell),
+    )
+}
+
+#[test]
+fn outermost_changes_cost_a_single_plate() {
+    let empty = dumbbell(vec![]);
+    let loaded = dumbbell(vec![20000]);
+    let heavier = dumbbell(vec![20000, 2500]);
+
+    assert_eq!(empty.change_cost(&loaded, &UnitCost), Some(1));
+    assert_eq!(heavier.change_cost(&loaded, &UnitCost), Some(1));
+    assert_eq!(empty.change_cost(&heavier, &UnitCost), None);
+}
+
+#[test]
+fn inner_changes_count_outer_plates_moved() {
+    let loaded = dumbbell(vec![20000, 5000, 2500]);
+
+    let inserted = dumbbell(vec![20000, 10000, 5000, 2500]);
+    let removed = dumbbell(vec![20000, 2500]);
+    let replaced = dumbbell(vec![20000, 10000, 2500]);
+
+    assert_eq!(loaded.change_cost(&inserted, &UnitCost), Some(5));
+    assert_eq!(loaded.change_cost(&removed, &UnitCost), Some(3));
+    assert_eq!(loaded.change_cost(&replaced, &UnitCost), Some(4));
+    assert_eq!(loaded.change_cost(&replaced, &PlateCountCost), Some(8));
+}
+
+#[test]
+fn multiple_plate_changes_are_not_adjacent() {
+    let loaded = dumbbell(vec![20000, 5000]);
+
+    assert!(!loaded.adjacent(&loaded));
+    assert!(!loaded.adjacent(&dumbbell(vec![10000, 2500])));
+    assert!(!loaded.adjacent(&dumbbell(vec![20000, 5000, 2500, 1250])));
+}
+
+#[test]
+fn pairs_change_both_handles() {
+    let bar = Bar::new(5000, 2, BarKind::Dumbbell);
+    let pair = Dumbbell::new(Plate::from_weights(vec![2500], 2), bar.clone());
+    let heavier_pair = Dumbbell::new(Plate::from_weights(vec![2500, 1250], 2), bar.clone());
+    let single = Dumbbell::with_handles(Plate::from_weights(vec![2500], 2), bar.clone(), 1);
+    let heavier_single =
+        Dumbbell::with_handles(Plate::from_weights(vec![2500, 1250], 2), bar.clone(), 1);
+
+    assert_eq!(pair.weight(), single.weight());
+    assert_eq!(pair.change_cost(&heavier_pair, &UnitCost), Some(2));
+    assert_eq!(single.change_cost(&heavier_single, &UnitCost), Some(1));
+    assert_eq!(pair.change_cost(&single, &UnitCost), Some(1));
+    assert_eq!(heavier_pair.change_cost(&single, &UnitCost), None);
+}
+
+#[test]
+fn collars_are_weighed_and_handled_on_every_change() {
+    let bar = Bar::new(2000, 1, BarKind::Dumbbell).with_collar(Collar::new(250, 1));
+    let empty = Dumbbell::new(vec![], bar.clone());
+    let loaded = Dumbbell::new(Plate::from_weights(vec![1250], 1), bar.clone());
+    let heavier = Dumbbell::new(Plate::from_weights(vec![1250, 500], 1), bar.clone());
+
+    assert_eq!(empty.weight(), 2000);
+    assert_eq!(loaded.weight(), 5000);
+    assert_eq!(heavier.weight(), 6000);
+
+    assert_eq!(empty.change_cost(&loaded, &UnitCost), Some(4));
+    assert_eq!(loaded.change_cost(&heavier, &UnitCost), Some(6));
+}
+
+#[test]
+fn prefixes_add_a_plate_at_a_time() {
+    let prefixes = dumbbell(vec![5000, 20000, 10000]).prefixes();
+
+    assert_eq!(
+        prefixes,
+        vec![
+            dumbbell(vec![]),
+            dumbbell(vec![20000]),
+            dumbbell(vec![20000, 10000]),
+            dumbbell(vec![20000, 10000, 5000]),
+        ]
+    );
+    assert!(
+        prefixes
+            .iter()
+            .tuple_windows()
+            .all(|(d1, d2)| d1.adjacent(d2))
+    );
+}
@@ -0,0 +1,786 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use workout_rs::{
+    bar::Bar, bar_kind::BarKind, block::Block, collar::Collar, cost_model::UnitCost,
+    dumbbell::Dumbbell, gym::Gym, gym_error::GymError, gym_state::GymState, plan::Change,
+    plan::Plan, plan::Step, plate::Plate, requirement::Requirement, rounding::Rounding,
+    superset::Superset,
+};
+
+fn dumbbells<'a>(plan: &'a Plan, bar: &Bar) -> Vec<&'a Dumbbell> {
+    plan.steps()
+        .iter()
+        .flat_map(Step::dumbbells)
+        .filter(|dumbbell| dumbbell.bar() == bar)
+        .collect()
+}
+
+fn weights(plan: &Plan, bar: &Bar) -> Vec<u32> {
+    dumbbells(plan, bar)
+        .iter()
+        .map(|dumbbell| dumbbell.weight())
+        .collect()
+}
+
+fn barbell_gym() -> Gym {
+    let bar = Bar::new(15000, 2, BarKind::Barbell);
+    let plates = Plate::from_weights_map(
+        HashMap::from([(20000, 2), (10000, 2), (5000, 4), (2500, 2)]),
+        2,
+    );
+
+    Gym::new(&plates, &[bar], &UnitCost)
+}
+
+#[test]
+fn repeated_requirement_stays_on_the_same_state() {
+    let bar = Bar::new(2000, 1, BarKind::Dumbbell);
+    let plates = Plate::from_weights_map(HashMap::from([(500, 4), (1250, 4)]), 1);
+    let gym = Gym::new(&plates, std::slice::from_ref(&bar), &UnitCost);
+
+    let ordered = gym
+        .order(&[
+            Requirement::new(4500, BarKind::Dumbbell),
+            Requirement::new(4500, BarKind::Dumbbell),
+        ])
+        .unwrap();
+
+    assert_eq!(weights(&ordered, &bar), vec![4500, 4500]);
+    assert_eq!(dumbbells(&ordered, &bar)[0], dumbbells(&ordered, &bar)[1]);
+}
+
+#[test]
+fn single_state_kind_is_part_of_the_graph() {
+    let dumbbell = Bar::new(2000, 1, BarKind::Dumbbell);
+    let barbell = Bar::new(15000, 2, BarKind::Barbell);
+    let plates = Plate::from_weights_map(HashMap::from([(500, 4), (1250, 4), (2500, 4)]), 1);
+    let gym = Gym::new(&plates, &[dumbbell.clone(), barbell.clone()], &UnitCost);
+
+    let ordered = gym
+        .order(&[
+            Requirement::new(15000, BarKind::Barbell),
+            Requirement::new(3000, BarKind::Dumbbell),
+            Requirement::new(15000, BarKind::Barbell),
+            Requirement::new(10500, BarKind::Dumbbell),
+        ])
+        .unwrap();
+
+    assert_eq!(weights(&ordered, &barbell), vec![15000, 15000]);
+    assert_eq!(weights(&ordered, &dumbbell), vec![3000, 10500]);
+}
+
+#[test]
+fn states_without_neighbours_are_reachable_from_themselves() {
+    let first = Bar::new(2000, 1, BarKind::Dumbbell);
+    let second = Bar::new(5000, 2, BarKind::Dumbbell);
+    let gym = Gym::new(&HashMap::new(), &[first.clone(), second.clone()], &UnitCost);
+
+    let ordered = gym
+        .order(&[
+            Requirement::new(5000, BarKind::Dumbbell),
+            Requirement::new(2000, BarKind::Dumbbell),
+            Requirement::new(5000, BarKind::Dumbbell),
+        ])
+        .unwrap();
+
+    assert_eq!(weights(&ordered, &second), vec![5000, 5000]);
+    assert_eq!(weights(&ordered, &first), vec![2000]);
+}
+
+#[test]
+fn every_state_of_a_kind_is_connected() {
+    let bar = Bar::new(15000, 2, BarKind::Barbell);
+    let plates = Plate::from_weights_map(
+        HashMap::from([(1250, 2), (2500, 2), (5000, 2), (10000, 2), (20000, 2)]),
+        2,
+    );
+    let gym = Gym::new(&plates, std::slice::from_ref(&bar), &UnitCost);
+
+    let requirements = gym.weights()[&bar]
+        .iter()
+        .rev()
+        .map(|weight| Requirement::new(*weight, BarKind::Barbell))
+        .collect::<Vec<_>>();
+
+    let ordered = gym.order(&requirements).unwrap();
+
+    assert_eq!(
+        weights(&ordered, &bar),
+        requirements
+            .iter()
+            .map(Requirement::weight)
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn impossible_requirement_is_rejected() {
+    let bar = Bar::new(2000, 1, BarKind::Dumbbell);
+    let plates = Plate::from_weights_map(HashMap::from([(500, 4)]), 1);
+    let gym = Gym::new(&plates, std::slice::from_ref(&bar), &UnitCost);
+
+    assert!(
+        gym.order(&[Requirement::new(2500, BarKind::Dumbbell)])
+            .is_err()
+    );
+}
+
+#[test]
+fn impossible_weights_are_rounded() {
+    let bar = Bar::new(2000, 1, BarKind::Dumbbell);
+    let plates = Plate::from_weights_map(HashMap::from([(1250, 4)]), 1);
+    let gym = Gym::new(&plates, std::slice::from_ref(&bar), &UnitCost).with_rounding(Rounding::Up);
+
+    let error = gym
+        .order(&[
+            Requirement::new(4000, BarKind::Dumbbell),
+            Requirement::new(4000, BarKind::Dumbbell).with_rounding(Rounding::Down),
+            Requirement::new(4000, BarKind::Dumbbell).with_rounding(Rounding::Exact),
+        ])
+        .unwrap_err();
+
+    assert!(matches!(error, GymError::InvalidRequirement(_)));
+
+    let ordered = gym
+        .order(&[
+            Requirement::new(4000, BarKind::Dumbbell),
+            Requirement::new(4000, BarKind::Dumbbell).with_rounding(Rounding::Down),
+            Requirement::new(5000, BarKind::Dumbbell).with_rounding(Rounding::Nearest),
+        ])
+        .unwrap();
+
+    assert_eq!(weights(&ordered, &bar), vec![4500, 2000, 4500]);
+    assert!(
+        ordered
+            .to_string()
+            .contains("= Dumbbell (1): [1.25] (4.5kg) + [1.25] (4.5kg) instead of 4kg (+0.5kg)")
+    );
+}
+
+#[test]
+fn cheapest_weight_in_range_is_picked() {
+    let bar = Bar::new(2000, 1, BarKind::Dumbbell);
+    let plates = Plate::from_weights_map(HashMap::from([(1250, 4), (2500, 4)]), 1);
+    let gym = Gym::new(&plates, std::slice::from_ref(&bar), &UnitCost);
+
+    let plan = gym
+        .order(&[
+            Requirement::new(7000, BarKind::Dumbbell),
+            Requirement::new(4000, BarKind::Dumbbell).up_to(9000),
+            Requirement::new(4500, BarKind::Dumbbell),
+        ])
+        .unwrap();
+
+    assert_eq!(weights(&plan, &bar), vec![7000, 7000, 4500]);
+    assert_eq!(plan.cost(), 4);
+}
+
+#[test]
+fn sets_are_planned_and_numbered_per_exercise() {
+    let dumbbell = Bar::new(2000, 2, BarKind::Dumbbell);
+    let barbell = Bar::new(15000, 2, BarKind::Barbell);
+    let plates = Plate::from_weights_map(HashMap::from([(2500, 6)]), 2);
+    let gym = Gym::new(&plates, &[dumbbell, barbell], &UnitCost);
+
+    let blocks = ["press:2x8@7d", "row:2@7d+squat:3x5@20b"].map(|block| block.parse().unwrap());
+    let plan = gym.order_with(None, &blocks, false).unwrap();
+
+    let headings = plan
+        .to_string()
+        .lines()
+        .filter(|line| !line.starts_with(' '))
+        .map(str::to_string)
+        .collect_vec();
+    assert_eq!(
+        headings,
+        vec![
+            "1. press: 7kg Dumbbell, set 1 of 2, 8 reps",
+            "2. press: 7kg Dumbbell, set 2 of 2, 8 reps",
+            "3. row: 7kg Dumbbell, set 1 of 2 + squat: 20kg Barbell, set 1 of 3, 5 reps",
+            "4. row: 7kg Dumbbell, set 2 of 2 + squat: 20kg Barbell, set 2 of 3, 5 reps",
+            "5. squat: 20kg Barbell, set 3 of 3, 5 reps",
+            "Total cost: 0",
+        ]
+    );
+}
+
+#[test]
+fn repeated_exercises_are_numbered_per_occurrence() {
+    let barbell = Bar::new(20000, 2, BarKind::Barbell);
+    let plates = Plate::from_weights_map(HashMap::from([(10000, 2)]), 2);
+    let gym = Gym::new(&plates, &[barbell], &UnitCost);
+
+    let blocks = ["bench:2@40b", "20b", "bench:2@40b"].map(|block| block.parse().unwrap());
+    let plan = gym.order_with(None, &blocks, false).unwrap();
+
+    let headings = plan
+        .to_string()
+        .lines()
+        .filter(|line| !line.starts_with(' '))
+        .map(str::to_string)
+        .collect_vec();
+    assert_eq!(
+        headings,
+        vec![
+            "1. bench: 40kg Barbell, set 1 of 2",
+            "2. bench: 40kg Barbell, set 2 of 2",
+            "3. 20kg Barbell",
+            "4. bench: 40kg Barbell, set 1 of 2",
+            "5. bench: 40kg Barbell, set 2 of 2",
+            "Total cost: 2",
+        ]
+    );
+}
+
+#[test]
+fn warm_ups_add_a_plate_at_a_time() {
+    let gym = barbell_gym();
+    let requirement = Requirement::new(85000, BarKind::Barbell);
+
+    let ramp = gym.warm_up_steps(&requirement, 3).unwrap();
+    assert_eq!(
+        ramp.iter().map(Dumbbell::weight).collect_vec(),
+        vec![15000, 55000, 75000, 85000]
+    );
+    assert!(ramp.iter().tuple_windows().all(|(d1, d2)| d1.adjacent(d2)));
+
+    let ramp = gym.warm_up(&requirement, &[50.0, 90.0]).unwrap();
+    assert_eq!(
+        ramp.iter().map(Dumbbell::weight).collect_vec(),
+        vec![55000, 75000, 85000]
+    );
+
+    for percentage in [-10.0, 0.0, 100.0, 150.0, f64::NAN] {
+        assert!(matches!(
+            gym.warm_up(&requirement, &[50.0, percentage]),
+            Err(GymError::InvalidPercentage(_))
+        ));
+    }
+
+    assert!(matches!(
+        gym.warm_up_steps(&Requirement::new(86000, BarKind::Barbell), 3),
+        Err(GymError::InvalidRequirement(_))
+    ));
+}
+
+#[test]
+fn distances_are_kept_across_plans() {
+    let gym = barbell_gym();
+    let requirements =
+        [85000, 55000, 85000].map(|weight| Requirement::new(weight, BarKind::Barbell));
+
+    let first = gym.order(&requirements).unwrap();
+    assert_eq!(gym.cached_distances(), 2);
+
+    let second = gym.order(&requirements).unwrap();
+    assert_eq!(gym.cached_distances(), 2);
+    assert_eq!(first.to_string(), second.to_string());
+
+    let blocks = ["bench:3@85b", "75b"].map(|block| block.parse().unwrap());
+    gym.order_with(None, &blocks, false).unwrap();
+    assert_eq!(gym.cached_distances(), 3);
+}
+
+#[test]
+fn drop_sets_only_pull_outer_plates() {
+    let gym = barbell_gym();
+    let requirement = Requirement::new(85000, BarKind::Barbell);
+
+    let sets = gym.drop_sets(&requirement, 2).unwrap();
+    assert_eq!(
+        sets.iter().map(Dumbbell::weight).collect_vec(),
+        vec![85000, 75000, 55000]
+    );
+    assert!(
+        sets.iter()
+            .tuple_windows()
+            .all(|(d1, d2)| d1.plates().starts_with(d2.plates()) && d1.adjacent(d2))
+    );
+
+    let sets = gym.drop_sets_by(&requirement, 20.0).unwrap();
+    assert_eq!(
+        sets.iter().map(Dumbbell::weight).collect_vec(),
+        vec![85000, 75000, 55000]
+    );
+
+    let sets = gym.drop_sets_by(&requirement, 40.0).unwrap();
+    assert_eq!(
+        sets.iter().map(Dumbbell::weight).collect_vec(),
+        vec![85000, 55000, 15000]
+    );
+    assert!(sets[0].plates().len() > sets[1].plates().len() + 1);
+    assert!(
+        sets.iter()
+            .tuple_windows()
+            .all(|(d1, d2)| d1.plates().starts_with(d2.plates()))
+    );
+
+    let sets = gym.drop_sets(&requirement, usize::MAX).unwrap();
+    assert_eq!(sets.last().unwrap().weight(), 15000);
+
+    let sets = gym.drop_sets(&requirement, 0).unwrap();
+    assert_eq!(sets.iter().map(Dumbbell::weight).collect_vec(), vec![85000]);
+
+    for percentage in [-10.0, 0.0, 100.0, f64::NAN] {
+        assert!(matches!(
+            gym.drop_sets_by(&requirement, percentage),
+            Err(GymError::InvalidPercentage(_))
+        ));
+    }
+
+    assert!(matches!(
+        gym.drop_sets(&Requirement::new(86000, BarKind::Barbell), 2),
+        Err(GymError::InvalidRequirement(_))
+    ));
+}
+
+#[test]
+fn single_dumbbells_use_plates_a_pair_cannot() {
+    let bar = Bar::new(2000, 1, BarKind::Dumbbell);
+    let plates = Plate::from_weights_map(HashMap::from([(1250, 2)]), 1);
+    let gym = Gym::new(&plates, std::slice::from_ref(&bar), &UnitCost);
+
+    assert!(
+        gym.order(&[Requirement::new(4500, BarKind::Dumbbell)])
+            .is_err()
+    );
+
+    let ordered = gym
+        .order(&[Requirement::with_handles(4500, BarKind::Dumbbell, 1)])
+        .unwrap();
+
+    assert_eq!(weights(&ordered, &bar), vec![4500]);
+    assert_eq!(dumbbells(&ordered, &bar)[0].handles(), 1);
+}
+
+#[test]
+fn pair_and_single_weights_are_listed_apart() {
+    let bar = Bar::new(2000, 1, BarKind::Dumbbell);
+    let plates = Plate::from_weights_map(HashMap::from([(1250, 4)]), 1);
+    let gym = Gym::new(&plates, std::slice::from_ref(&bar), &UnitCost);
+
+    assert_eq!(gym.weights()[&bar], vec![2000, 4500]);
+    assert_eq!(gym.single_weights()[&bar], vec![2000, 4500, 7000]);
+
+    for weight in &gym.weights()[&bar] {
+        assert!(
+            gym.order(&[Requirement::new(*weight, BarKind::Dumbbell)])
+                .is_ok()
+        );
+    }
+    for weight in &gym.single_weights()[&bar] {
+        assert!(
+            gym.order(&[Requirement::with_handles(*weight, BarKind::Dumbbell, 1)])
+                .is_ok()
+        );
+    }
+}
+
+#[test]
+fn fixed_implements_are_used_without_plates() {
+    let kettlebell = Bar::new(16000, 0, BarKind::Fixed);
+    let ez_bar = Bar::new(10000, 2, BarKind::EzBar);
+    let plates = Plate::from_weights_map(HashMap::from([(2500, 2)]), 2);
+    let gym = Gym::new(&plates, &[kettlebell.clone(), ez_bar.clone()], &UnitCost);
+
+    assert_eq!(gym.weights()[&kettlebell], vec![16000]);
+
+    let ordered = gym
+        .order(&[
+            Requirement::new(16000, BarKind::Fixed),
+            Requirement::new(15000, BarKind::EzBar),
+            Requirement::new(16000, BarKind::Fixed),
+        ])
+        .unwrap();
+
+    assert_eq!(weights(&ordered, &kettlebell), vec![16000, 16000]);
+    assert!(dumbbells(&ordered, &kettlebell)[0].plates().is_empty());
+    assert_eq!(weights(&ordered, &ez_bar), vec![15000]);
+}
+
+#[test]
+fn pairs_of_fixed_implements_are_supersets() {
+    let first = Bar::new(16000, 0, BarKind::Fixed);
+    let second = Bar::new(16000, 0, BarKind::Fixed).with_id(1);
+    let gym = Gym::new(&HashMap::new(), &[first.clone(), second.clone()], &UnitCost);
+
+    let pair: Superset = "16f+16f".parse().unwrap();
+    let plan = gym.order_with(None, &[pair.into()], false).unwrap();
+
+    assert_eq!(
+        plan.steps()[0].dumbbells(),
+        vec![
+            &Dumbbell::new(vec![], first),
+            &Dumbbell::new(vec![], second)
+        ]
+    );
+    assert!(
+        gym.order(&[Requirement::new(16000, BarKind::Dumbbell)])
+            .is_err()
+    );
+}
+
+#[test]
+fn identical_bars_are_loaded_separately() {
+    let first = Bar::new(2000, 1, BarKind::Dumbbell);
+    let second = Bar::new(2000, 1, BarKind::Dumbbell)
+        .with_id(1)
+        .with_name("blue handle");
+    let plates = Plate::from_weights_map(HashMap::from([(1250, 8)]), 1);
+    let gym = Gym::new(&plates, &[first.clone(), second.clone()], &UnitCost);
+
+    assert_eq!(gym.weights().len(), 2);
+
+    let ordered = gym
+        .order(&[
+            Requirement::new(4500, BarKind::Dumbbell),
+            Requirement::new(2000, BarKind::Dumbbell),
+            Requirement::new(4500, BarKind::Dumbbell),
+            Requirement::new(2000, BarKind::Dumbbell),
+        ])
+        .unwrap();
+
+    assert_eq!(
+        ordered
+            .steps()
+            .iter()
+            .flat_map(Step::dumbbells)
+            .map(Dumbbell::bar)
+            .unique()
+            .count(),
+        2
+    );
+    assert_eq!(second.to_string(), "blue handle");
+}
+
+#[test]
+fn steps_name_the_bar_the_requirement_was_met_on() {
+    let heavy = Bar::new(3000, 1, BarKind::Dumbbell);
+    let light = Bar::new(2000, 1, BarKind::Dumbbell);
+    let plates = Plate::from_weights_map(HashMap::from([(1000, 4), (1500, 4)]), 1);
+    let gym = Gym::new(&plates, &[heavy.clone(), light.clone()], &UnitCost);
+    let start = GymState::new(HashMap::from([
+        (
+            heavy.clone(),
+            Dumbbell::new(Plate::from_weights(vec![1000], 1), heavy.clone()),
+        ),
+        (
+            light.clone(),
+            Dumbbell::new(Plate::from_weights(vec![1500], 1), light.clone()),
+        ),
+    ]));
+
+    let plan = gym
+        .order_with(
+            Some(&start),
+            &[Requirement::new(5000, BarKind::Dumbbell).into()],
+            false,
+        )
+        .unwrap();
+
+    assert_eq!(plan.cost(), 0);
+    assert_eq!(plan.steps()[0].dumbbells()[0].bar(), &heavy);
+}
+
+#[test]
+fn plan_lists_the_changes_between_steps_in_session_order() {
+    let dumbbell = Bar::new(2000, 1, BarKind::Dumbbell);
+    let barbell = Bar::new(15000, 2, BarKind::Barbell);
+    let plates = Plate::from_weights_map(HashMap::from([(1250, 4), (2500, 4)]), 1)
+        .into_iter()
+        .chain(Plate::from_weights_map(HashMap::from([(2500, 2)]), 2))
+        .collect();
+    let gym = Gym::new(&plates, &[dumbbell.clone(), barbell.clone()], &UnitCost);
+
+    let plan = gym
+        .order(&[
+            Requirement::new(4500, BarKind::Dumbbell),
+            Requirement::new(20000, BarKind::Barbell),
+            Requirement::new(7000, BarKind::Dumbbell),
+        ])
+        .unwrap();
+
+    let changes = plan
+        .steps()
+        .iter()
+        .map(|step| step.changes().iter().map(ToString::to_string).collect_vec())
+        .collect_vec();
+
+    assert_eq!(
+        changes,
+        vec![
+            vec!["Dumbbell (1): add 1.25kg on each handle"],
+            vec!["Barbell (2): add 2.5kg"],
+            vec!["Dumbbell (1): remove 1.25kg, add 2.5kg on each handle"],
+        ]
+    );
+    assert_eq!(
+        plan.steps()[1].state().get(&dumbbell).unwrap().weight(),
+        4500
+    );
+}
+
+#[test]
+fn collars_come_off_and_go_back_on_around_plate_changes() {
+    let bar = Bar::new(2000, 1, BarKind::Dumbbell).with_collar(Collar::new(250, 1));
+    let plates = Plate::from_weights_map(HashMap::from([(1250, 4), (500, 4)]), 1);
+    let gym = Gym::new(&plates, std::slice::from_ref(&bar), &UnitCost);
+
+    let plan = gym
+        .order_with(
+            None,
+            &["5d", "6d"].map(|block| block.parse().unwrap()),
+            true,
+        )
+        .unwrap();
+
+    assert_eq!(
+        plan.steps()[1].changes()[0].to_string(),
+        "Dumbbell (1): remove collar, add 0.5kg, refit collar on each handle"
+    );
+    assert_eq!(
+        plan.finish()[0].to_string(),
+        "Dumbbell (1): remove collar, remove 0.5kg, 1.25kg on each handle"
+    );
+
+    let bare = Dumbbell::new(vec![], bar.clone());
+    let change = Change::between(&bare, plan.steps()[0].dumbbells()[0]).unwrap();
+    assert_eq!(
+        change.to_string(),
+        "Dumbbell (1): add 1.25kg, fit collar on each handle"
+    );
+}
+
+#[test]
+fn start_and_strip_are_part_of_the_plan() {
+    let bar = Bar::new(15000, 2, BarKind::Barbell);
+    let plates = Plate::from_weights_map(HashMap::from([(5000, 2), (10000, 2)]), 2);
+    let gym = Gym::new(&plates, std::slice::from_ref(&bar), &UnitCost);
+    let start = GymState::new(HashMap::from([(
+        bar.clone(),
+        Dumbbell::new(Plate::from_weights(vec![10000], 2), bar.clone()),
+    )]));
+
+    let plan = gym
+        .order_with(
+            Some(&start),
+            &[Requirement::new(45000, BarKind::Barbell).into()],
+            true,
+        )
+        .unwrap();
+
+    assert_eq!(
+        plan.steps()[0].changes()[0].to_string(),
+        "Barbell (2): add 5kg"
+    );
+    assert_eq!(
+        plan.finish()[0].to_string(),
+        "Barbell (2): remove 5kg, 10kg"
+    );
+    assert!(plan.end().get(&bar).unwrap().plates().is_empty());
+    assert_eq!(plan.cost(), 3);
+
+    let unknown = GymState::new(HashMap::from([(
+        bar.clone(),
+        Dumbbell::new(Plate::from_weights(vec![20000], 2), bar.clone()),
+    )]));
+    assert!(gym.order_with(Some(&unknown), &[], false).is_err());
+}
+
+#[test]
+fn changing_several_bars_costs_the_sum_of_each_change() {
+    let small = Bar::new(2000, 1, BarKind::Dumbbell);
+    let big = Bar::new(5000, 2, BarKind::Dumbbell);
+    let plates = Plate::from_weights_map(HashMap::from([(2500, 4), (1250, 4), (500, 4)]), 1)
+        .into_iter()
+        .chain(Plate::from_weights_map(HashMap::from([(2500, 4)]), 2))
+        .collect();
+    let gym = Gym::new(&plates, &[small.clone(), big.clone()], &UnitCost);
+    let start = GymState::new(HashMap::from([
+        (
+            small.clone(),
+            Dumbbell::new(Plate::from_weights(vec![2500, 500], 1), small.clone()),
+        ),
+        (
+            big.clone(),
+            Dumbbell::new(Plate::from_weights(vec![2500], 2), big.clone()),
+        ),
+    ]));
+    let superset = Superset::new(vec![
+        Requirement::new(10500, BarKind::Dumbbell),
+        Requirement::new(5000, BarKind::Dumbbell),
+    ]);
+
+    let plan = gym
+        .order_with(Some(&start), &[superset.into()], false)
+        .unwrap();
+
+    assert_eq!(plan.steps().len(), 1);
+    assert_eq!(plan.steps()[0].changes().len(), 2);
+    assert_eq!(plan.cost(), 8);
+}
+
+#[test]
+fn fixed_implements_are_left_as_they_are_when_stripping() {
+    let kettlebell = Bar::new(16000, 0, BarKind::Fixed);
+    let barbell = Bar::new(15000, 2, BarKind::Barbell);
+    let plates = Plate::from_weights_map(HashMap::from([(5000, 2)]), 2);
+    let gym = Gym::new(&plates, &[kettlebell.clone(), barbell.clone()], &UnitCost);
+    let start = GymState::new(HashMap::from([(
+        barbell.clone(),
+        Dumbbell::new(Plate::from_weights(vec![5000], 2), barbell.clone()),
+    )]));
+
+    let plan = gym
+        .order_with(
+            Some(&start),
+            &[Requirement::new(16000, BarKind::Fixed).into()],
+            true,
+        )
+        .unwrap();
+
+    assert_eq!(plan.cost(), 1);
+    assert!(
+        plan.end()
+            .iter()
+            .all(|(_, dumbbell)| dumbbell.plates().is_empty())
+    );
+    assert_eq!(plan.end().get(&kettlebell).unwrap().weight(), 16000);
+}
+
+#[test]
+fn requirements_in_a_block_are_reordered_to_save_changes() {
+    let bar = Bar::new(15000, 2, BarKind::Barbell);
+    let plates = Plate::from_weights_map(HashMap::from([(5000, 2), (10000, 2)]), 2);
+    let gym = Gym::new(&plates, std::slice::from_ref(&bar), &UnitCost);
+    let requirements = [25000, 45000, 25000].map(|w| Requirement::new(w, BarKind::Barbell));
+
+    let fixed = gym.order(&requirements).unwrap();
+    let reordered = gym
+        .order_with(
+            None,
+            &[Block::new(
+                requirements.clone().map(Superset::from).to_vec(),
+            )],
+            false,
+        )
+        .unwrap();
+
+    assert_eq!(fixed.cost(), 6);
+    assert_eq!(reordered.cost(), 3);
+    assert!(
+        [vec![25000, 25000, 45000], vec![45000, 25000, 25000]].contains(&weights(&reordered, &bar))
+    );
+
+    let blocks = [
+        Requirement::new(45000, BarKind::Barbell).into(),
+        Block::new(requirements.map(Superset::from).to_vec()),
+    ];
+    let plan = gym.order_with(None, &blocks, false).unwrap();
+    assert_eq!(weights(&plan, &bar), vec![45000, 45000, 25000, 25000]);
+}
+
+#[test]
+fn supersets_are_loaded_at_once_from_shared_plates() {
+    let dumbbell = Bar::new(2000, 2, BarKind::Dumbbell);
+    let barbell = Bar::new(15000, 2, BarKind::Barbell);
+    let plates = Plate::from_weights_map(HashMap::from([(2500, 4)]), 2);
+    let gym = Gym::new(&plates, &[dumbbell.clone(), barbell.clone()], &UnitCost);
+
+    let superset = Superset::new(vec![
+        Requirement::with_handles(7000, BarKind::Dumbbell, 1),
+        Requirement::new(20000, BarKind::Barbell),
+    ]);
+    let plan = gym.order_with(None, &[superset.into()], false).unwrap();
+
+    assert_eq!(plan.steps().len(), 1);
+    assert_eq!(
+        plan.steps()[0]
+            .dumbbells()
+            .iter()
+            .map(|dumbbell| dumbbell.weight())
+            .collect_vec(),
+        vec![7000, 20000]
+    );
+
+    let superset = Superset::new(vec![
+        Requirement::new(7000, BarKind::Dumbbell),
+        Requirement::new(20000, BarKind::Barbell),
+    ]);
+    assert!(matches!(
+        gym.order_with(None, &[superset.into()], false),
+        Err(GymError::InvalidSuperset(_))
+    ));
+}
+
+#[test]
+fn shared_plates_are_split_between_kinds_loaded_together() {
+    let dumbbell = Bar::new(2000, 2, BarKind::Dumbbell);
+    let barbell = Bar::new(15000, 2, BarKind::Barbell);
+    let plates = Plate::from_weights_map(HashMap::from([(2500, 4), (1250, 4)]), 2);
+    let gym = Gym::new(&plates, &[dumbbell.clone(), barbell.clone()], &UnitCost);
+
+    // Loaded on its own, the barbell would take two of the 2.5kg plates the dumbbells need.
+    assert_eq!(
+        gym.order(&[Requirement::new(20000, BarKind::Barbell)])
+            .unwrap()
+            .steps()[0]
+            .dumbbells()[0]
+            .plates(),
+        Plate::from_weights(vec![2500], 2)
+    );
+
+    let superset = Superset::new(vec![
+        Requirement::new(7000, BarKind::Dumbbell),
+        Requirement::new(20000, BarKind::Barbell),
+    ]);
+    let plan = gym.order_with(None, &[superset.into()], false).unwrap();
+
+    assert_eq!(
+        plan.steps()[0]
+            .dumbbells()
+            .iter()
+            .map(|dumbbell| dumbbell.plates().to_vec())
+            .collect_vec(),
+        vec![
+            Plate::from_weights(vec![2500], 2),
+            Plate::from_weights(vec![1250, 1250], 2),
+        ]
+    );
+}
+
+#[test]
+fn kinds_are_solved_together_in_session_order() {
+    let dumbbell = Bar::new(2000, 2, BarKind::Dumbbell);
+    let barbell = Bar::new(15000, 2, BarKind::Barbell);
+    let plates = Plate::from_weights_map(HashMap::from([(2500, 4)]), 2);
+    let gym = Gym::new(&plates, &[dumbbell.clone(), barbell.clone()], &UnitCost);
+
+    let plan = gym
+        .order(&[
+            Requirement::new(20000, BarKind::Barbell),
+            Requirement::new(7000, BarKind::Dumbbell),
+            Requirement::new(20000, BarKind::Barbell),
+        ])
+        .unwrap();
+
+    assert_eq!(
+        plan.steps()
+            .iter()
+            .map(|step| step.changes().iter().map(ToString::to_string).collect_vec())
+            .collect_vec(),
+        vec![
+            vec!["Barbell (2): add 2.5kg"],
+            vec![
+                "Dumbbell (2): add 2.5kg on each handle",
+                "Barbell (2): remove 2.5kg",
+            ],
+            vec![
+                "Dumbbell (2): handle 1: no change; handle 2: remove 2.5kg",
+                "Barbell (2): add 2.5kg",
+            ],
+        ]
+    );
+    assert_eq!(plan.cost(), 3);
+}
@@ -0,0 +1,42 @@
+use std::path::PathBuf;
+
+use workout_rs::{bar_kind::BarKind, inventory::Inventory, inventory_error::InventoryError};
+
+fn write(name: &str, contents: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("workout-{name}-{}", std::process::id()));
+    std::fs::write(&path, contents).unwrap();
+
+    path
+}
+
+fn load(name: &str, kind: BarKind, bars: &str) -> Result<Inventory, InventoryError> {
+    let plates = write(&format!("{name}-plates"), "[]");
+    let collars = write(&format!("{name}-collars"), "[]");
+    let bars = write(&format!("{name}-bars"), bars);
+    let inventory = Inventory::load(&plates, &collars, &[(kind, &bars)]);
+
+    for path in [plates, collars, bars] {
+        std::fs::remove_file(path).unwrap();
+    }
+
+    inventory
+}
+
+#[test]
+fn fixed_implements_need_no_gauge() {
+    let inventory = load("fixed", BarKind::Fixed, r#"[{ "weight": 16000 }]"#).unwrap();
+
+    assert_eq!(inventory.bars()[0].weight(), 16000);
+}
+
+#[test]
+fn loadable_bars_need_a_gauge() {
+    let error = load(
+        "gauge",
+        BarKind::Barbell,
+        r#"[{ "weight": 20000, "gauge": 2 }, { "weight": 15000 }]"#,
+    )
+    .unwrap_err();
+
+    assert!(matches!(error, InventoryError::MissingGauge(_, 2)));
+}
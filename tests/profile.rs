@@ -0,0 +1,67 @@
+use std::collections::HashMap;
+
+use workout_rs::{
+    bar::Bar, bar_kind::BarKind, cost_model::UnitCost, gym::Gym, plate::Plate, profile::Profile,
+    profile_error::ProfileError, requirement::Requirement, rounding::Rounding,
+};
+
+fn profile() -> Profile {
+    Profile::new(HashMap::from([(
+        ("bench".to_string(), BarKind::Barbell),
+        100_000,
+    )]))
+}
+
+#[test]
+fn percentages_are_taken_of_the_lift_max() {
+    let requirement = profile().resolve(&"bench:82.5%b".parse().unwrap()).unwrap();
+
+    assert_eq!(requirement.weight(), 82500);
+    assert_eq!(requirement.percent(), Some(82.5));
+    assert_eq!(requirement.rounding(), None);
+    assert_eq!(requirement.to_string(), "bench: 82.5% = 82.5kg Barbell");
+
+    let requirement = profile()
+        .resolve(&"bench:80%b/down".parse().unwrap())
+        .unwrap();
+    assert_eq!(requirement.rounding(), Some(Rounding::Down));
+}
+
+#[test]
+fn percentages_follow_the_gym_rounding() {
+    let bar = Bar::new(15000, 2, BarKind::Barbell);
+    let plates = Plate::from_weights_map(
+        HashMap::from([(20000, 2), (10000, 2), (5000, 2), (2500, 2)]),
+        2,
+    );
+    let requirement = profile().resolve(&"bench:84%b".parse().unwrap()).unwrap();
+
+    let weight = |rounding: Rounding| {
+        let gym = Gym::new(&plates, std::slice::from_ref(&bar), &UnitCost).with_rounding(rounding);
+        let plan = gym.order(std::slice::from_ref(&requirement)).unwrap();
+        plan.steps()[0].dumbbells()[0].weight()
+    };
+
+    assert_eq!(weight(Rounding::Exact), 85000);
+    assert_eq!(weight(Rounding::Nearest), 85000);
+    assert_eq!(weight(Rounding::Down), 80000);
+}
+
+#[test]
+fn set_weights_are_left_alone() {
+    let requirement: Requirement = "bench:60b".parse().unwrap();
+
+    assert_eq!(profile().resolve(&requirement).unwrap(), requirement);
+}
+
+#[test]
+fn lifts_without_a_max_are_rejected() {
+    assert!(matches!(
+        profile().resolve(&"bench:80%d".parse().unwrap()),
+        Err(ProfileError::MissingMax(_, BarKind::Dumbbell))
+    ));
+    assert!(matches!(
+        profile().resolve(&"squat:80%b".parse().unwrap()),
+        Err(ProfileError::MissingMax(..))
+    ));
+}
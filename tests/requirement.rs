@@ -0,0 +1,110 @@
+use workout_rs::{
+    bar::Bar, bar_kind::BarKind, dumbbell::Dumbbell, requirement::Requirement, rounding::Rounding,
+};
+
+fn parse(s: &str) -> Requirement {
+    s.parse().unwrap()
+}
+
+#[test]
+fn parses_every_bar_kind() {
+    for (s, bar_kind) in [
+        ("22.5d", BarKind::Dumbbell),
+        ("60b", BarKind::Barbell),
+        ("20e", BarKind::EzBar),
+        ("70t", BarKind::TrapBar),
+        ("24f", BarKind::Fixed),
+    ] {
+        let requirement = parse(s);
+        assert_eq!(requirement.bar_kind(), bar_kind);
+        assert_eq!(requirement.handles(), bar_kind.handles());
+    }
+}
+
+#[test]
+fn parses_single_dumbbells() {
+    let requirement = parse("12.5s");
+
+    assert_eq!(requirement.weight(), 12500);
+    assert_eq!(requirement.bar_kind(), BarKind::Dumbbell);
+    assert_eq!(requirement.handles(), 1);
+}
+
+#[test]
+fn rejects_unknown_bar_kinds() {
+    assert!("20x".parse::<Requirement>().is_err());
+    assert!("twentyd".parse::<Requirement>().is_err());
+}
+
+#[test]
+fn parses_rounding() {
+    assert_eq!(parse("23d").rounding(), None);
+    assert_eq!(parse("23d/nearest").rounding(), Some(Rounding::Nearest));
+    assert_eq!(parse("23d/down").rounding(), Some(Rounding::Down));
+
+    let requirement = parse("60b/2.5");
+    assert_eq!(requirement.rounding(), Some(Rounding::Within(2500)));
+    assert_eq!(requirement.bounds(), (57500, 62500));
+
+    assert!("23d/sideways".parse::<Requirement>().is_err());
+}
+
+#[test]
+fn parses_ranges_and_tolerances() {
+    let requirement = parse("20-24d");
+    assert_eq!(requirement.range(), (20000, 24000));
+    assert_eq!(requirement.bounds(), (20000, 24000));
+    assert_eq!(requirement.to_string(), "20-24kg Dumbbell");
+
+    let requirement = parse("60b~2.5");
+    assert_eq!(requirement.range(), (60000, 60000));
+    assert_eq!(requirement.bounds(), (57500, 62500));
+
+    assert!("24-20d".parse::<Requirement>().is_err());
+    assert!("60b~up".parse::<Requirement>().is_err());
+}
+
+#[test]
+fn rejects_empty_requirements() {
+    assert!("".parse::<Requirement>().is_err());
+    assert!("d".parse::<Requirement>().is_err());
+}
+
+#[test]
+fn parses_exercise_names_and_percentages() {
+    let requirement = parse("incline:22.5d");
+    assert_eq!(requirement.label(), Some("incline"));
+    assert_eq!(requirement.weight(), 22500);
+    assert_eq!(requirement.percent(), None);
+
+    let requirement = parse("bench:80%b");
+    assert_eq!(requirement.label(), Some("bench"));
+    assert_eq!(requirement.percent(), Some(80.0));
+
+    assert!("80%b".parse::<Requirement>().is_err());
+    assert!(":60b".parse::<Requirement>().is_err());
+}
+
+#[test]
+fn parses_sets_and_reps() {
+    let requirement = parse("incline:3x10@22.5d");
+    assert_eq!(requirement.label(), Some("incline"));
+    assert_eq!(requirement.sets(), 3);
+    assert_eq!(requirement.reps(), Some(10));
+    assert_eq!(requirement.weight(), 22500);
+
+    let requirement = parse("4@60b");
+    assert_eq!(requirement.sets(), 4);
+    assert_eq!(requirement.reps(), None);
+
+    assert_eq!(parse("60b").sets(), 1);
+    assert!("0x10@60b".parse::<Requirement>().is_err());
+    assert!("3xten@60b".parse::<Requirement>().is_err());
+}
+
+#[test]
+fn huge_tolerances_match_without_overflowing() {
+    let bar = Bar::new(20000, 2, BarKind::Barbell);
+
+    assert!(parse("60b~5000000").matches(&Dumbbell::new(vec![], bar)));
+}
@@ -0,0 +1,56 @@
+use workout_rs::rounding::Rounding;
+
+const ACHIEVABLE: [u32; 4] = [10000, 12500, 15000, 20000];
+
+#[test]
+fn rounds_to_achievable_weights() {
+    assert_eq!(
+        Rounding::Exact.bounds((13000, 13000), &ACHIEVABLE),
+        Some((13000, 13000))
+    );
+    assert_eq!(
+        Rounding::Down.bounds((13000, 13000), &ACHIEVABLE),
+        Some((12500, 12500))
+    );
+    assert_eq!(
+        Rounding::Up.bounds((13000, 13000), &ACHIEVABLE),
+        Some((15000, 15000))
+    );
+    assert_eq!(
+        Rounding::Nearest.bounds((13000, 13000), &ACHIEVABLE),
+        Some((12500, 13500))
+    );
+    assert_eq!(
+        Rounding::Within(1000).bounds((13000, 13000), &ACHIEVABLE),
+        Some((12000, 14000))
+    );
+}
+
+#[test]
+fn nothing_to_round_to() {
+    assert_eq!(Rounding::Down.bounds((9000, 9000), &ACHIEVABLE), None);
+    assert_eq!(Rounding::Up.bounds((21000, 21000), &ACHIEVABLE), None);
+    assert_eq!(Rounding::Nearest.bounds((13000, 13000), &[]), None);
+}
+
+#[test]
+fn ranges_keep_every_weight_in_them() {
+    assert_eq!(
+        Rounding::Nearest.bounds((11000, 13000), &ACHIEVABLE),
+        Some((11000, 13000))
+    );
+    assert_eq!(
+        Rounding::Up.bounds((16000, 17000), &ACHIEVABLE),
+        Some((20000, 20000))
+    );
+}
+
+#[test]
+fn huge_tolerances_accept_anything() {
+    let rounding = "5000000".parse::<Rounding>().unwrap();
+
+    assert_eq!(
+        rounding.bounds((60000, 60000), &ACHIEVABLE),
+        Some((0, u32::MAX))
+    );
+}
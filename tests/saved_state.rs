@@ -0,0 +1,70 @@
+use std::collections::HashMap;
+
+use workout_rs::{
+    bar::Bar, bar_kind::BarKind, dumbbell::Dumbbell, gym_state::GymState, plate::Plate,
+    saved_state::SavedState,
+};
+
+#[test]
+fn state_round_trips_through_the_file() {
+    let bars = [
+        Bar::new(2000, 1, BarKind::Dumbbell),
+        Bar::new(2000, 1, BarKind::Dumbbell).with_id(1),
+        Bar::new(15000, 2, BarKind::Barbell).with_name("comp bar"),
+    ];
+    let state = GymState::new(HashMap::from([
+        (
+            bars[1].clone(),
+            Dumbbell::with_handles(Plate::from_weights(vec![1250], 1), bars[1].clone(), 1),
+        ),
+        (
+            bars[2].clone(),
+            Dumbbell::new(Plate::from_weights(vec![20000, 5000], 2), bars[2].clone()),
+        ),
+        (bars[0].clone(), Dumbbell::new(vec![], bars[0].clone())),
+    ]));
+
+    let saved = SavedState::from_state(&state, &bars);
+    assert_eq!(
+        saved
+            .loadings()
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>(),
+        vec!["s2:1.25", "comp bar:20,5"]
+    );
+
+    let path = std::env::temp_dir().join(format!("workout-state-{}.json", std::process::id()));
+    saved.save(&path).unwrap();
+    let loaded = SavedState::load(&path).unwrap().unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded, saved);
+    assert_eq!(
+        loaded.state(&bars).unwrap(),
+        GymState::new(HashMap::from([
+            (bars[1].clone(), state.get(&bars[1]).unwrap().clone()),
+            (bars[2].clone(), state.get(&bars[2]).unwrap().clone()),
+        ]))
+    );
+    assert!(SavedState::load(&path).unwrap().is_none());
+}
+
+#[test]
+fn single_handles_of_named_bars_round_trip() {
+    let bars = [Bar::new(2000, 1, BarKind::Dumbbell).with_name("blue handle")];
+    let state = GymState::new(HashMap::from([(
+        bars[0].clone(),
+        Dumbbell::with_handles(Plate::from_weights(vec![1250], 1), bars[0].clone(), 1),
+    )]));
+
+    let saved = SavedState::from_state(&state, &bars);
+    assert_eq!(saved.loadings()[0].to_string(), "s:blue handle:1.25");
+
+    let path = std::env::temp_dir().join(format!("workout-named-{}.json", std::process::id()));
+    saved.save(&path).unwrap();
+    let loaded = SavedState::load(&path).unwrap().unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded.state(&bars).unwrap(), state);
+}
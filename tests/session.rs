@@ -0,0 +1,143 @@
+use std::path::PathBuf;
+
+use workout_rs::{session::Session, session_error::SessionError, superset::Superset};
+
+fn read(name: &str, contents: &str) -> Result<Session, SessionError> {
+    let path = std::env::temp_dir().join(format!("workout-{name}-{}", std::process::id()));
+    std::fs::write(&path, contents).unwrap();
+    let session = Session::read(&path);
+    std::fs::remove_file(&path).unwrap();
+
+    session
+}
+
+fn shape(session: &Session) -> Vec<Vec<Vec<String>>> {
+    session
+        .blocks()
+        .iter()
+        .map(|block| {
+            block
+                .supersets()
+                .iter()
+                .map(|superset: &Superset| {
+                    superset
+                        .requirements()
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect()
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn reads_text_sessions() {
+    let session = read(
+        "session.txt",
+        "# push day\nincline press: 3x10 @ 16d  # heavy\n\ncurl:12e, row:21d+squat:60b\n",
+    )
+    .unwrap();
+
+    assert_eq!(
+        shape(&session),
+        vec![
+            vec![vec!["incline press: 16kg Dumbbell"]],
+            vec![
+                vec!["curl: 12kg EZ Bar"],
+                vec!["row: 21kg Dumbbell", "squat: 60kg Barbell"]
+            ],
+        ]
+    );
+    assert_eq!(
+        session.blocks()[0].supersets()[0].requirements()[0].sets(),
+        3
+    );
+}
+
+#[test]
+fn reads_json_sessions() {
+    let session = read(
+        "session.json",
+        r#"[
+            "incline:16d",
+            ["curl:12e", { "exercise": "row", "sets": 2, "reps": 8, "weight": "21d" }],
+            [["press:16d", "squat:60b"]]
+        ]"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        shape(&session),
+        vec![
+            vec![vec!["incline: 16kg Dumbbell"]],
+            vec![vec!["curl: 12kg EZ Bar"], vec!["row: 21kg Dumbbell"]],
+            vec![vec!["press: 16kg Dumbbell", "squat: 60kg Barbell"]],
+        ]
+    );
+    let row = &session.blocks()[1].supersets()[1].requirements()[0];
+    assert_eq!((row.sets(), row.reps()), (2, Some(8)));
+}
+
+#[test]
+fn exercises_can_ask_for_a_percentage_of_their_max() {
+    let session = read(
+        "percent.json",
+        r#"[{ "exercise": "bench", "sets": 3, "weight": "80%b" }]"#,
+    )
+    .unwrap();
+
+    let bench = &session.blocks()[0].supersets()[0].requirements()[0];
+    assert_eq!(bench.label(), Some("bench"));
+    assert_eq!((bench.sets(), bench.percent()), (3, Some(80.0)));
+}
+
+#[test]
+fn exercises_only_override_what_they_give() {
+    let session = read(
+        "override.json",
+        r#"[
+            { "weight": "3x10@22.5d", "reps": 8 },
+            { "exercise": "incline", "weight": "incline:2x12@16d" }
+        ]"#,
+    )
+    .unwrap();
+
+    let first = &session.blocks()[0].supersets()[0].requirements()[0];
+    assert_eq!((first.sets(), first.reps()), (3, Some(8)));
+
+    let incline = &session.blocks()[1].supersets()[0].requirements()[0];
+    assert_eq!(incline.label(), Some("incline"));
+    assert_eq!((incline.sets(), incline.reps()), (2, Some(12)));
+}
+
+#[test]
+fn contradicting_exercises_are_rejected() {
+    assert!(matches!(
+        read("zero.json", r#"[{ "sets": 0, "weight": "22.5d" }]"#),
+        Err(SessionError::Entry(..))
+    ));
+    assert!(matches!(
+        read(
+            "labels.json",
+            r#"[{ "exercise": "incline", "weight": "bench:80%b" }]"#
+        ),
+        Err(SessionError::Entry(..))
+    ));
+}
+
+#[test]
+fn reports_the_line_of_invalid_requirements() {
+    assert!(matches!(
+        read("invalid.txt", "16d\n\n20x\n"),
+        Err(SessionError::Line(_, 3, _))
+    ));
+    assert!(matches!(
+        read("invalid.json", r#"[{ "weight": "20x" }]"#),
+        Err(SessionError::Entry(..))
+    ));
+    assert!(matches!(
+        Session::read(&PathBuf::from("/nonexistent/session.txt")),
+        Err(SessionError::Io(..))
+    ));
+}
@@ -0,0 +1,51 @@
+use workout_rs::{
+    bar_kind::BarKind,
+    bar_loading::BarLoading,
+    requirement::Requirement,
+    unit::{Unit, UnitDisplay, Weight},
+};
+
+#[test]
+fn parses_weights_in_either_unit() {
+    assert_eq!(Unit::parse_weight("22.5", Unit::Kg), Ok(22500));
+    assert_eq!(Unit::parse_weight("20kg", Unit::Lb), Ok(20000));
+    assert_eq!(Unit::parse_weight("45lb", Unit::Kg), Ok(20412));
+    assert_eq!(Unit::parse_weight("4.35", Unit::Kg), Ok(4350));
+    assert!(Unit::parse_weight("-5", Unit::Kg).is_err());
+    assert!(Unit::parse_weight("lb", Unit::Kg).is_err());
+}
+
+#[test]
+fn pound_requirements_match_pound_plates() {
+    let requirement: Requirement = "135lbb".parse().unwrap();
+    assert_eq!(requirement.bar_kind(), BarKind::Barbell);
+
+    let loaded = 3 * Unit::Lb.grams(45.0);
+    assert_ne!(loaded, requirement.weight());
+    assert_eq!(requirement.offset(loaded), 0);
+    assert_eq!(requirement.offset(loaded + 500), 501);
+}
+
+#[test]
+fn displays_weights_in_either_unit() {
+    assert_eq!(Weight(20412).to_string(), "20.412kg");
+    assert_eq!(Weight(20412).in_unit(Unit::Lb).to_string(), "45lb");
+    assert_eq!(Weight(1134).in_unit(Unit::Lb).to_string(), "2.5lb");
+
+    let requirement: Requirement = "bench:135lbb~5lb".parse().unwrap();
+    assert_eq!(
+        requirement.in_unit(Unit::Lb).to_string(),
+        "bench: 135lb Barbell (within 5lb)"
+    );
+
+    let loading: BarLoading = "b:45lb,10lb".parse().unwrap();
+    assert_eq!(loading.in_unit(Unit::Lb).to_string(), "b:45lb,10lb");
+    assert_eq!(
+        loading
+            .in_unit(Unit::Lb)
+            .to_string()
+            .parse::<BarLoading>()
+            .unwrap(),
+        loading
+    );
+}